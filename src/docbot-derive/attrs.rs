@@ -1,66 +1,76 @@
-use anyhow::{anyhow, Context};
+use anyhow::anyhow;
 use proc_macro2::Span;
-use syn::{spanned::Spanned, Attribute, Lit, Meta, MetaNameValue};
+use syn::{spanned::Spanned, Attribute, Expr, ExprLit, Lit, Meta, MetaNameValue};
 
 use crate::{
+    diag::Errors,
     docs::{CommandDocs, CommandSetDocs, ParseDocs},
-    opts::{CommandOpts, FieldOpts, ParseOpts},
+    opts::{CommandOpts, CommandSetOpts, FieldOpts, ParseOpts},
     Result,
 };
 
-fn parse_core<O: ParseOpts, D: ParseDocs>(attrs: &[Attribute], span: Span) -> Result<(O, D)> {
+fn parse_core<O: ParseOpts, D: ParseDocs>(
+    attrs: &[Attribute],
+    span: Span,
+) -> Result<(O, D), Errors> {
+    let mut errs = Errors::default();
     let mut opts = None;
     let mut docs = Vec::new();
 
     for attr in attrs {
-        match attr.path.get_ident() {
+        match attr.path().get_ident() {
             Some(i) if i == "doc" => {
-                let meta = attr
-                    .parse_meta()
-                    .context("failed to parse doc comment")
-                    .map_err(|e| (e, attr.span()))?;
-
                 if let Meta::NameValue(MetaNameValue {
-                    lit: ref l @ Lit::Str(ref s),
+                    value:
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(ref s),
+                            ..
+                        }),
                     ..
-                }) = meta
+                }) = attr.meta
                 {
-                    docs.push((s.value(), l.span()));
+                    docs.push((s.value(), s.span()));
                 } else {
-                    return Err((anyhow!("unexpected doc comment format"), attr.span()));
+                    errs.push((anyhow!("unexpected doc comment format"), attr.span()));
                 }
             },
             Some(i) if i == "docbot" => {
-                if let Some(..) = &opts {
-                    return Err((anyhow!("multiple #[docbot] attributes found"), attr.span()));
+                if opts.is_some() {
+                    errs.push((anyhow!("multiple #[docbot] attributes found"), attr.span()));
+                } else {
+                    opts = Some(errs.check(O::parse_opts(attr)));
                 }
-
-                opts = Some(O::parse_opts(attr)?);
             },
             _ => (),
         }
     }
 
-    Ok((
-        opts.map_or_else(O::no_opts, Ok).map_err(|e| (e, span))?,
-        if docs.is_empty() {
-            D::no_docs().map_err(|e| (e, span))
-        } else {
-            D::parse_docs(docs, span)
-        }?,
-    ))
+    let opts = opts.unwrap_or_else(|| errs.check(O::no_opts().map_err(|e| (e, span))));
+    let docs = errs.check(if docs.is_empty() {
+        D::no_docs().map_err(|e| (e, span))
+    } else {
+        D::parse_docs(docs, span)
+    });
+
+    // Both of these are present if no errors were recorded
+    errs.finish(opts.zip(docs)).map(Option::unwrap)
 }
 
-pub fn parse_command(attrs: &[Attribute], span: Span) -> Result<(CommandOpts, CommandDocs)> {
+pub fn parse_command(
+    attrs: &[Attribute],
+    span: Span,
+) -> Result<(CommandOpts, CommandDocs), Errors> {
     parse_core(attrs, span)
 }
 
-pub fn parse_enum(attrs: &[Attribute], span: Span) -> Result<CommandSetDocs> {
-    let ((), ret) = parse_core(attrs, span)?;
-    Ok(ret)
+pub fn parse_enum(
+    attrs: &[Attribute],
+    span: Span,
+) -> Result<(CommandSetOpts, CommandSetDocs), Errors> {
+    parse_core(attrs, span)
 }
 
-pub fn parse_field(attrs: &[Attribute], span: Span) -> Result<FieldOpts> {
+pub fn parse_field(attrs: &[Attribute], span: Span) -> Result<FieldOpts, Errors> {
     let (ret, ()) = parse_core(attrs, span)?;
     Ok(ret)
 }
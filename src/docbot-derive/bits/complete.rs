@@ -0,0 +1,77 @@
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+
+use super::id::IdParts;
+use crate::inputs::prelude::*;
+
+pub struct CompleteParts {
+    pub items: TokenStream,
+}
+
+/// Emit completions for `cmd`, the command at position `index` in the type's
+/// command tree
+fn complete_command(span: Span, cmd: &Command, index: usize, args: &Ident) -> TokenStream {
+    if cmd.opts.subcommand {
+        let ty = cmd.fields.iter().next().unwrap().ty;
+
+        quote_spanned! { span =>
+            <#ty as ::docbot::Complete>::complete(#args.iter().copied())
+        }
+    } else {
+        quote_spanned! { span =>
+            <Self as ::docbot::Help>::tree().commands[#index].usage.complete_args(#args)
+        }
+    }
+}
+
+pub fn emit(input: &InputData, id_parts: &IdParts) -> CompleteParts {
+    let id = Ident::new("__id", input.span);
+    let args = Ident::new("__args", input.span);
+    let id_ty = &id_parts.ty;
+
+    let arms: Vec<_> = match input.commands {
+        Commands::Struct { ref command, .. } => {
+            let complete = complete_command(input.span, command, 0, &args);
+
+            vec![quote_spanned! { input.span => _ => #complete }]
+        },
+        Commands::Enum { ref variants, .. } => variants
+            .iter()
+            .enumerate()
+            .map(
+                |(
+                    i,
+                    CommandVariant {
+                        span,
+                        ident,
+                        command,
+                        ..
+                    },
+                )| {
+                    let complete = complete_command(*span, command, i, &args);
+
+                    quote_spanned! { *span => #id_ty::#ident => #complete }
+                },
+            )
+            .collect(),
+    };
+
+    // Quote variables
+    let name = input.ty;
+    let (impl_vars, ty_vars, where_clause) = input.generics.split_for_impl();
+
+    let items = quote_spanned! { input.span =>
+        impl #impl_vars ::docbot::Complete for #name #ty_vars #where_clause {
+            fn complete_args(
+                #id: #id_ty,
+                #args: &[&str],
+            ) -> ::std::vec::Vec<::docbot::Completion> {
+                match #id {
+                    #(#arms),*
+                }
+            }
+        }
+    };
+
+    CompleteParts { items }
+}
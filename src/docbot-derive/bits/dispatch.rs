@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote_spanned};
+use syn::{parse_quote, Type};
+
+use crate::inputs::prelude::*;
+
+pub struct DispatchParts {
+    pub items: Option<TokenStream>,
+}
+
+struct Route<'a> {
+    span: Span,
+    pat: TokenStream,
+    target: Target<'a>,
+}
+
+enum Target<'a> {
+    Method {
+        ident: Ident,
+        doc: Literal,
+        params: Vec<(Ident, &'a Type)>,
+        args: Vec<Ident>,
+    },
+    Subcommand(&'a Type, Ident),
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut ret = String::new();
+
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                ret.push('_');
+            }
+
+            ret.extend(c.to_lowercase());
+        } else {
+            ret.push(c);
+        }
+    }
+
+    ret
+}
+
+/// Convert an argument name from a usage string to `snake_case`, returning
+/// `None` if it doesn't start with a letter
+fn param_name(name: &str) -> Option<String> {
+    let mut ret = String::new();
+    let mut sep = false;
+    let mut prev_lower = false;
+
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            sep = true;
+            prev_lower = false;
+            continue;
+        }
+
+        if (sep || (c.is_ascii_uppercase() && prev_lower)) && !ret.is_empty() {
+            ret.push('_');
+        }
+
+        sep = false;
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        ret.push(c.to_ascii_lowercase());
+    }
+
+    ret.starts_with(|c: char| c.is_ascii_alphabetic())
+        .then_some(ret)
+}
+
+/// Construct an identifier from a valid identifier or keyword, escaping it as
+/// a raw identifier if necessary
+///
+/// Returns `None` for keywords that can't be raw identifiers.
+fn escape_ident(s: &str, span: Span) -> Option<Ident> {
+    if syn::parse_str::<Ident>(s).is_ok() {
+        Some(Ident::new(s, span))
+    } else if matches!(s, "crate" | "self" | "super" | "Self" | "_") {
+        None
+    } else {
+        Some(Ident::new_raw(s, span))
+    }
+}
+
+/// Construct the handler parameter for the field at `index`, named after its
+/// argument if possible
+fn param_ident(name: &str, index: usize, span: Span) -> Ident {
+    param_name(name)
+        .and_then(|p| escape_ident(&p, span))
+        .unwrap_or_else(|| format_ident!("arg{}", index, span = span))
+}
+
+fn route<'a>(span: Span, path: TokenStream, name: &Ident, cmd: &'a Command) -> Route<'a> {
+    let (pat, mut bindings): (_, Vec<_>) = match cmd.fields {
+        FieldInfos::Unit => (path, vec![]),
+        FieldInfos::Unnamed(ref u) => {
+            let vars: Vec<_> = (0..u.len())
+                .map(|i| format_ident!("__arg{}", i, span = span))
+                .collect();
+            let bindings = vars
+                .iter()
+                .zip(u)
+                .map(|(var, f)| (param_ident(&f.name, f.index, f.span), var.clone(), f))
+                .collect();
+
+            (quote_spanned! { span => #path(#(#vars),*) }, bindings)
+        },
+        FieldInfos::Named(ref n) => {
+            let vars = n.iter().map(|(i, _)| i);
+            let bindings = n.iter().map(|(i, f)| (i.clone(), i.clone(), f)).collect();
+
+            (quote_spanned! { span => #path { #(#vars),* } }, bindings)
+        },
+    };
+
+    let target = if cmd.opts.subcommand {
+        let (_, var, field) = bindings.into_iter().next().unwrap();
+
+        Target::Subcommand(field.ty, var)
+    } else {
+        let doc = Literal::string(&format!(
+            "Handle the `{}` command",
+            cmd.docs.usage.ids.first().map_or("", String::as_str)
+        ));
+
+        // Fields are listed in the order of the command's usage, but handler
+        // parameters follow the order the fields are declared in
+        bindings.sort_by_key(|(_, _, f)| f.index);
+
+        let mut seen = HashSet::new();
+        let (params, args) = bindings
+            .into_iter()
+            .map(|(param, var, f)| {
+                let param = if seen.insert(param.to_string()) {
+                    param
+                } else {
+                    format_ident!("arg{}", f.index, span = f.span)
+                };
+
+                ((param, f.ty), var)
+            })
+            .unzip();
+
+        let method = to_snake_case(&name.to_string());
+
+        Target::Method {
+            ident: escape_ident(&method, name.span())
+                .unwrap_or_else(|| format_ident!("{}_", method, span = name.span())),
+            doc,
+            params,
+            args,
+        }
+    };
+
+    Route { span, pat, target }
+}
+
+/// The dispatch traits the derive can implement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    /// `Dispatch`, routing to a synchronous handler
+    Sync,
+    /// `AsyncDispatch`, routing to an asynchronous handler
+    Async,
+    /// `SendAsyncDispatch`, routing to an asynchronous handler whose futures
+    /// are `Send`
+    Send,
+}
+
+impl Flavor {
+    fn is_async(self) -> bool { self != Self::Sync }
+}
+
+fn handler_name(input: &InputData, is_async: bool) -> Ident {
+    if is_async {
+        format_ident!("{}AsyncHandler", input.ty, span = input.ty.span())
+    } else {
+        format_ident!("{}Handler", input.ty, span = input.ty.span())
+    }
+}
+
+/// Returns the generics for the handler trait declaration, the generics used to
+/// name the handler trait within the dispatch impl, and the generics of the
+/// dispatch impl itself
+fn emit_generics(
+    input: &InputData,
+    routes: &[Route],
+    trait_name: &Ident,
+    dispatch_trait: &TokenStream,
+) -> (Generics, Generics, Generics) {
+    let mut trait_generics = input.generics.clone();
+    trait_generics.params.push(parse_quote! { Ctx });
+
+    let mut trait_ty_generics = input.generics.clone();
+    trait_ty_generics.params.push(parse_quote! { __Ctx });
+    let (_, trait_ty_vars, _) = trait_ty_generics.split_for_impl();
+
+    let mut impl_generics = trait_ty_generics.clone();
+    impl_generics
+        .params
+        .push(parse_quote! { __H: ?Sized + #trait_name #trait_ty_vars });
+
+    let where_clause = impl_generics.make_where_clause();
+
+    for r in routes {
+        if let Target::Subcommand(ty, _) = r.target {
+            where_clause.predicates.push(parse_quote! {
+                #ty: #dispatch_trait<
+                    __H,
+                    __Ctx,
+                    Output = <__H as #trait_name #trait_ty_vars>::Output,
+                >
+            });
+        }
+    }
+
+    (trait_generics, trait_ty_generics, impl_generics)
+}
+
+/// Emit the handler trait called by the dispatch impls
+fn emit_handler(input: &InputData, routes: &[Route], is_async: bool, send: bool) -> TokenStream {
+    let vis = input.vis;
+    let trait_name = handler_name(input, is_async);
+
+    let (ret_ty, doc) = if is_async {
+        let send = send.then(|| quote_spanned! { input.span => + ::std::marker::Send });
+
+        (
+            quote_spanned! { input.span =>
+                impl ::std::future::Future<Output = Self::Output> #send
+            },
+            format!(
+                "Asynchronous handler for commands of type {}, used by \
+                 [`AsyncDispatch`](::docbot::AsyncDispatch)",
+                input.ty
+            ),
+        )
+    } else {
+        (
+            quote_spanned! { input.span => Self::Output },
+            format!(
+                "Handler for commands of type {}, used by [`Dispatch`](::docbot::Dispatch)",
+                input.ty
+            ),
+        )
+    };
+    let doc = Literal::string(&doc);
+
+    let methods = routes.iter().filter_map(|r| match r.target {
+        Target::Method {
+            ref ident,
+            ref doc,
+            ref params,
+            ..
+        } => {
+            let params = params
+                .iter()
+                .map(|(p, t)| quote_spanned! { r.span => #p: #t });
+
+            Some(quote_spanned! { r.span =>
+                #[doc = #doc]
+                fn #ident(&mut self, __ctx: Ctx, #(#params),*) -> #ret_ty;
+            })
+        },
+        Target::Subcommand(..) => None,
+    });
+
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote! { Ctx });
+    let (trait_vars, _, trait_where) = generics.split_for_impl();
+
+    quote_spanned! { input.span =>
+        #[doc = #doc]
+        #vis trait #trait_name #trait_vars #trait_where {
+            /// The value returned by this handler
+            type Output;
+
+            #(#methods)*
+        }
+    }
+}
+
+/// Emit an implementation of the dispatch trait for `flavor`
+fn emit_impl(input: &InputData, routes: &[Route], flavor: Flavor) -> TokenStream {
+    let name = input.ty;
+    let handler = Ident::new("__handler", input.span);
+    let ctx = Ident::new("__ctx", input.span);
+    let trait_name = handler_name(input, flavor.is_async());
+
+    let (dispatch_trait, dispatch_fn) = match flavor {
+        Flavor::Sync => (
+            quote_spanned! { input.span => ::docbot::Dispatch },
+            quote_spanned! { input.span => dispatch },
+        ),
+        Flavor::Async => (
+            quote_spanned! { input.span => ::docbot::AsyncDispatch },
+            quote_spanned! { input.span => dispatch_async },
+        ),
+        Flavor::Send => (
+            quote_spanned! { input.span => ::docbot::SendAsyncDispatch },
+            quote_spanned! { input.span => dispatch_send },
+        ),
+    };
+    let (async_, await_) = if flavor.is_async() {
+        (
+            Some(quote_spanned! { input.span => async }),
+            Some(quote_spanned! { input.span => .await }),
+        )
+    } else {
+        (None, None)
+    };
+
+    let arms = routes.iter().map(|r| {
+        let pat = &r.pat;
+
+        match r.target {
+            Target::Method {
+                ref ident,
+                ref args,
+                ..
+            } => quote_spanned! { r.span =>
+                #pat => #handler.#ident(#ctx, #(#args),*)#await_
+            },
+            Target::Subcommand(_, ref var) => quote_spanned! { r.span =>
+                #pat => #dispatch_trait::#dispatch_fn(#var, #handler, #ctx)#await_
+            },
+        }
+    });
+
+    let (_, trait_ty_generics, mut impl_generics) =
+        emit_generics(input, routes, &trait_name, &dispatch_trait);
+    let (_, trait_ty_vars, _) = trait_ty_generics.split_for_impl();
+    let (_, ty_vars, _) = input.generics.split_for_impl();
+
+    // The future holds the command, the handler, and the context
+    if flavor == Flavor::Send {
+        let where_clause = impl_generics.make_where_clause();
+
+        where_clause
+            .predicates
+            .push(parse_quote! { #name #ty_vars: ::std::marker::Send });
+        where_clause
+            .predicates
+            .push(parse_quote! { __H: ::std::marker::Send });
+        where_clause
+            .predicates
+            .push(parse_quote! { __Ctx: ::std::marker::Send });
+    }
+
+    let (impl_vars, _, impl_where) = impl_generics.split_for_impl();
+
+    // SendAsyncDispatch takes its output type from AsyncDispatch
+    let output = (flavor != Flavor::Send).then(|| {
+        quote_spanned! { input.span =>
+            type Output = <__H as #trait_name #trait_ty_vars>::Output;
+        }
+    });
+
+    quote_spanned! { input.span =>
+        impl #impl_vars #dispatch_trait<__H, __Ctx> for #name #ty_vars #impl_where {
+            #output
+
+            #async_ fn #dispatch_fn(self, #handler: &mut __H, #ctx: __Ctx) -> Self::Output {
+                match self { #(#arms),* }
+            }
+        }
+    }
+}
+
+pub fn emit(input: &InputData) -> DispatchParts {
+    let opts = input.commands.set_opts();
+
+    if opts.is_empty() {
+        return DispatchParts { items: None };
+    }
+
+    let routes: Vec<_> = match input.commands {
+        Commands::Struct { ref command, .. } => {
+            vec![route(
+                input.span,
+                quote_spanned! { input.span => Self },
+                input.ty,
+                command,
+            )]
+        },
+        Commands::Enum { ref variants, .. } => variants
+            .iter()
+            .map(
+                |CommandVariant {
+                     span,
+                     ident,
+                     command,
+                     ..
+                 }| {
+                    route(
+                        *span,
+                        quote_spanned! { *span => Self::#ident },
+                        ident,
+                        command,
+                    )
+                },
+            )
+            .collect(),
+    };
+
+    let sync = opts.dispatch.then(|| {
+        let handler = emit_handler(input, &routes, false, false);
+        let imp = emit_impl(input, &routes, Flavor::Sync);
+
+        quote_spanned! { input.span => #handler #imp }
+    });
+    let async_ = opts.async_dispatch.then(|| {
+        let handler = emit_handler(input, &routes, true, opts.send_dispatch);
+        let imp = emit_impl(input, &routes, Flavor::Async);
+        let send = opts
+            .send_dispatch
+            .then(|| emit_impl(input, &routes, Flavor::Send));
+
+        quote_spanned! { input.span => #handler #imp #send }
+    });
+
+    DispatchParts {
+        items: Some(quote_spanned! { input.span => #sync #async_ }),
+    }
+}
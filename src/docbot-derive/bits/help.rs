@@ -1,5 +1,6 @@
-use proc_macro2::{Literal, TokenStream};
-use quote::quote_spanned;
+use proc_macro2::{Literal, TokenStream, TokenTree};
+use quote::{quote_spanned, ToTokens};
+use syn::{GenericParam, Type};
 
 use super::path::PathParts;
 use crate::inputs::prelude::*;
@@ -16,27 +17,152 @@ fn emit_bool(span: Span, b: bool) -> TokenStream {
     }
 }
 
+fn emit_flag(span: Span, flag: Option<&FlagArg>) -> TokenStream {
+    fn opt_str(span: Span, s: Option<&String>) -> TokenStream {
+        s.map_or_else(
+            || quote_spanned! { span => None },
+            |s| {
+                let s = Literal::string(s);
+
+                quote_spanned! { span => Some(#s) }
+            },
+        )
+    }
+
+    flag.map_or_else(
+        || quote_spanned! { span => None },
+        |FlagArg { short, long, value }| {
+            let short = opt_str(span, short.as_ref());
+            let long = opt_str(span, long.as_ref());
+            let value = opt_str(span, value.as_ref());
+
+            quote_spanned! { span =>
+                Some(::docbot::FlagUsage {
+                    short: #short,
+                    long: #long,
+                    value: #value,
+                })
+            }
+        },
+    )
+}
+
+fn emit_key(span: Span, id: &str, attr: Option<&str>) -> TokenStream {
+    let id = Literal::string(id);
+    let attr = attr.map_or_else(
+        || quote_spanned! { span => None },
+        |attr| {
+            let attr = Literal::string(attr);
+
+            quote_spanned! { span => Some(#attr) }
+        },
+    );
+
+    quote_spanned! { span =>
+        ::docbot::MessageKey {
+            id: #id,
+            attr: #attr,
+        }
+    }
+}
+
+/// Get the message attribute for the description of an argument, replacing any
+/// characters not allowed in a Fluent identifier
+fn arg_attr(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    format!("arg-{name}")
+}
+
+/// Returns true if `ty` refers to any of the parameters in `generics`
+fn uses_generics(ty: &Type, generics: &Generics) -> bool {
+    fn visit(tokens: TokenStream, params: &[&Ident]) -> bool {
+        tokens.into_iter().any(|t| match t {
+            TokenTree::Ident(ref i) => params.contains(&i),
+            TokenTree::Group(g) => visit(g.stream(), params),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+        })
+    }
+
+    let params: Vec<_> = generics
+        .params
+        .iter()
+        .map(|p| match p {
+            GenericParam::Type(t) => &t.ident,
+            GenericParam::Lifetime(l) => &l.lifetime.ident,
+            GenericParam::Const(c) => &c.ident,
+        })
+        .collect();
+
+    !params.is_empty() && visit(ty.to_token_stream(), &params)
+}
+
+/// Emit a function returning the canonical values of `ty` if it implements
+/// `ValueHint`, for use in a static
+///
+/// Statics can't refer to the generic parameters of the command type, so no
+/// values are given for types that use them.
+fn emit_value_hint(span: Span, ty: Option<&Type>, generics: &Generics) -> TokenStream {
+    if let Some(ty) = ty.filter(|t| !uses_generics(t, generics)) {
+        quote_spanned! { span =>
+            || {
+                #[allow(unused_imports)]
+                use ::docbot::__private::{HasValueHint as _, NoValueHint as _};
+
+                (&::docbot::__private::Probe::<#ty>(::std::marker::PhantomData))
+                    .canonical_values()
+            }
+        }
+    } else {
+        quote_spanned! { span => || None }
+    }
+}
+
 struct ArgUsage<'a> {
     name: &'a str,
     required: bool,
     rest: bool,
+    flag: Option<&'a FlagArg>,
 }
 
-fn emit_usage(docs: &CommandDocs) -> TokenStream {
-    let CommandDocs { span, usage, .. } = docs;
+pub fn emit_usage(cmd: &Command) -> TokenStream {
+    let CommandDocs {
+        span,
+        usage,
+        permissions,
+        ..
+    } = &cmd.docs;
     let ids = usage.ids.iter().map(|i| Literal::string(i));
+    let permissions = permissions.iter().map(|p| Literal::string(p));
     let args = usage
-        .required
+        .flags
         .iter()
-        .map(|n| ArgUsage {
+        .map(|f| ArgUsage {
+            name: f.name(),
+            required: false,
+            rest: false,
+            flag: Some(f),
+        })
+        .chain(usage.required.iter().map(|n| ArgUsage {
             name: n,
             required: true,
             rest: false,
-        })
+            flag: None,
+        }))
         .chain(usage.optional.iter().map(|n| ArgUsage {
             name: n,
             required: false,
             rest: false,
+            flag: None,
         }))
         .chain(match usage.rest {
             RestArg::None => None,
@@ -44,11 +170,13 @@ fn emit_usage(docs: &CommandDocs) -> TokenStream {
                 name: n,
                 required: false,
                 rest: true,
+                flag: None,
             }),
             RestArg::Required(ref n) => Some(ArgUsage {
                 name: n,
                 required: true,
                 rest: true,
+                flag: None,
             }),
         })
         .map(
@@ -56,32 +184,56 @@ fn emit_usage(docs: &CommandDocs) -> TokenStream {
                  name,
                  required,
                  rest,
+                 flag,
              }| {
+                let path =
+                    flag.is_none() && cmd.fields.iter().any(|f| f.name == name && f.opts.path);
                 let name = Literal::string(name);
                 let required = emit_bool(*span, required);
                 let rest = emit_bool(*span, rest);
+                let path = emit_bool(*span, path);
+                let flag = emit_flag(*span, flag);
 
                 quote_spanned! { *span =>
                     ::docbot::ArgumentUsage {
                         name: #name,
                         is_required: #required,
                         is_rest: #rest,
+                        is_path: #path,
+                        flag: #flag,
                     }
                 }
             },
         );
     let desc = Literal::string(&usage.desc);
+    let desc_key = emit_key(*span, &cmd.key, None);
+    let deprecated = cmd.opts.deprecated.as_ref().map_or_else(
+        || quote_spanned! { *span => None },
+        |note| {
+            let note = Literal::string(note);
+
+            quote_spanned! { *span => Some(#note) }
+        },
+    );
 
     quote_spanned! { *span =>
         ::docbot::CommandUsage {
             ids: &[#(#ids),*],
             args: &[#(#args),*],
-            desc: #desc
+            desc: #desc,
+            desc_key: #desc_key,
+            permissions: &[#(#permissions),*],
+            deprecated: #deprecated,
         }
     }
 }
 
-fn emit_desc(docs: &CommandDocs) -> TokenStream {
+fn emit_desc(
+    key: &str,
+    docs: &CommandDocs,
+    fields: &FieldInfos,
+    generics: &Generics,
+) -> TokenStream {
     let summary = docs.summary.as_ref().map_or_else(
         || quote_spanned! { docs.span => None },
         |summary| {
@@ -92,6 +244,23 @@ fn emit_desc(docs: &CommandDocs) -> TokenStream {
     );
 
     let args = docs.args.iter().map(|(name, required, desc)| {
+        let flag = emit_flag(
+            docs.span,
+            docs.usage.flags.iter().find(|f| f.name() == name),
+        );
+        let field = fields.iter().find(|f| f.name == *name);
+        let default = match field.and_then(|f| f.opts.default.as_ref()) {
+            Some(FieldDefault::Value(s)) => quote_spanned! { docs.span => Some(#s) },
+            Some(FieldDefault::Trait) | None => quote_spanned! { docs.span => None },
+        };
+        let desc_key = emit_key(docs.span, key, Some(&arg_attr(name)));
+        let ty = Literal::string(&field.map(FieldInfo::type_name).unwrap_or_default());
+        // Command paths span several arguments, so listing only their heads
+        // would be misleading
+        let value_type = field
+            .filter(|f| !f.opts.path)
+            .and_then(FieldInfo::value_type);
+        let value_hint = emit_value_hint(docs.span, value_type, generics);
         let name = Literal::string(name);
         let required = emit_bool(docs.span, *required);
         let desc = Literal::string(desc);
@@ -100,7 +269,12 @@ fn emit_desc(docs: &CommandDocs) -> TokenStream {
             ::docbot::ArgumentDesc {
                 name: #name,
                 is_required: #required,
+                flag: #flag,
+                default: #default,
                 desc: #desc,
+                desc_key: #desc_key,
+                ty: #ty,
+                value_hint: #value_hint,
             }
         }
     });
@@ -114,11 +288,16 @@ fn emit_desc(docs: &CommandDocs) -> TokenStream {
         },
     );
 
+    let summary_key = emit_key(docs.span, key, Some("summary"));
+    let examples_key = emit_key(docs.span, key, Some("examples"));
+
     quote_spanned! { docs.span =>
         ::docbot::CommandDesc {
             summary: #summary,
+            summary_key: #summary_key,
             args: &[#(#args),*],
             examples: #examples,
+            examples_key: #examples_key,
         }
     }
 }
@@ -140,18 +319,80 @@ fn get_topic_pats(
     }
 }
 
+fn emit_node(span: Span, cmd: &Command, generics: &Generics) -> TokenStream {
+    let usage = emit_usage(cmd);
+    let desc = emit_desc(&cmd.key, &cmd.docs, &cmd.fields, generics);
+    let hidden = emit_bool(span, cmd.opts.hidden);
+
+    let subcommands = if cmd.opts.subcommand {
+        let ty = cmd.fields.iter().next().unwrap().ty;
+
+        quote_spanned! { span => Some(<#ty as ::docbot::Help>::tree) }
+    } else {
+        quote_spanned! { span => None }
+    };
+
+    quote_spanned! { span =>
+        ::docbot::CommandNode {
+            usage: #usage,
+            desc: #desc,
+            hidden: #hidden,
+            subcommands: #subcommands,
+        }
+    }
+}
+
+fn emit_variant_arm(
+    path_ty: &Ident,
+    CommandVariant {
+        span,
+        ident,
+        command: cmd @ Command {
+            key, docs, fields, ..
+        },
+        ..
+    }: &CommandVariant,
+    generics: &Generics,
+) -> TokenStream {
+    let usage = emit_usage(cmd);
+    let desc = emit_desc(key, docs, fields, generics);
+
+    let topic = quote_spanned! { *span =>
+        static __TOPIC: ::docbot::HelpTopic =
+            ::docbot::HelpTopic::Command(
+                #usage,
+                #desc,
+            );
+
+        &__TOPIC
+    };
+
+    let (path_pat, ret) = get_topic_pats(*span, cmd, topic);
+
+    quote_spanned! { *span =>
+        Some(#path_ty::#ident #path_pat) => { #ret }
+    }
+}
+
 pub fn emit(input: &InputData, path_parts: &PathParts) -> HelpParts {
     let path_ty = &path_parts.ty;
     let topic_arms;
     let general_help;
+    let tree;
 
     match input.commands {
         Commands::Struct {
-            command: ref cmd @ Command { ref docs, .. },
+            command:
+                ref cmd @ Command {
+                    ref key,
+                    ref docs,
+                    ref fields,
+                    ..
+                },
             ..
         } => {
-            let usage = emit_usage(docs);
-            let desc = emit_desc(docs);
+            let usage = emit_usage(cmd);
+            let desc = emit_desc(key, docs, fields, input.generics);
 
             general_help = quote_spanned! { docs.span =>
                 ::docbot::HelpTopic::Command(#usage, #desc)
@@ -162,12 +403,25 @@ pub fn emit(input: &InputData, path_parts: &PathParts) -> HelpParts {
             let (path_pat, ret) = get_topic_pats(docs.span, cmd, topic);
 
             topic_arms = vec![quote_spanned! { docs.span => Some(#path_ty #path_pat) => { #ret } }];
+
+            let node = emit_node(docs.span, cmd, input.generics);
+            let summary_key = emit_key(docs.span, key, Some("summary"));
+
+            tree = quote_spanned! { docs.span =>
+                ::docbot::CommandTree {
+                    summary: None,
+                    summary_key: #summary_key,
+                    commands: &[#node],
+                }
+            };
         },
         Commands::Enum {
+            ref key,
             ref docs,
             ref variants,
             ..
         } => {
+            let summary_key = emit_key(docs.span, key, Some("summary"));
             let summary = docs.summary.as_ref().map_or_else(
                 || quote_spanned! { docs.span => None },
                 |summary| {
@@ -177,46 +431,30 @@ pub fn emit(input: &InputData, path_parts: &PathParts) -> HelpParts {
                 },
             );
 
-            let commands = variants.iter().map(
-                |CommandVariant {
-                     command: Command { docs, .. },
-                     ..
-                 }| emit_usage(docs),
-            );
+            let commands = variants
+                .iter()
+                .filter(|v| !v.command.opts.hidden)
+                .map(|v| emit_usage(&v.command));
 
             general_help = quote_spanned! { docs.span =>
-                ::docbot::HelpTopic::CommandSet(#summary, &[#(#commands),*])
+                ::docbot::HelpTopic::CommandSet(#summary, #summary_key, &[#(#commands),*])
+            };
+
+            let nodes = variants.iter().map(|CommandVariant { span, command, .. }| {
+                emit_node(*span, command, input.generics)
+            });
+
+            tree = quote_spanned! { docs.span =>
+                ::docbot::CommandTree {
+                    summary: #summary,
+                    summary_key: #summary_key,
+                    commands: &[#(#nodes),*],
+                }
             };
 
             topic_arms = variants
                 .iter()
-                .map(
-                    |CommandVariant {
-                         span,
-                         ident,
-                         command: cmd @ Command { docs, .. },
-                         ..
-                     }| {
-                        let usage = emit_usage(docs);
-                        let desc = emit_desc(docs);
-
-                        let topic = quote_spanned! { *span =>
-                            static __TOPIC: ::docbot::HelpTopic =
-                                ::docbot::HelpTopic::Command(
-                                    #usage,
-                                    #desc,
-                                );
-
-                            &__TOPIC
-                        };
-
-                        let (path_pat, ret) = get_topic_pats(*span, cmd, topic);
-
-                        quote_spanned! { *span =>
-                            Some(#path_ty::#ident #path_pat) => { #ret }
-                        }
-                    },
-                )
+                .map(|v| emit_variant_arm(path_ty, v, input.generics))
                 .collect();
         },
     }
@@ -236,6 +474,12 @@ pub fn emit(input: &InputData, path_parts: &PathParts) -> HelpParts {
                         None => &__GENERAL,
                     }
                 }
+
+                fn tree() -> &'static ::docbot::CommandTree {
+                    static __TREE: ::docbot::CommandTree = #tree;
+
+                    &__TREE
+                }
             }
         })
     } else {
@@ -1,7 +1,7 @@
 use proc_macro2::{Literal, TokenStream};
 use quote::{format_ident, quote_spanned, ToTokens};
 
-use crate::inputs::prelude::*;
+use crate::{inputs::prelude::*, trie::Trie};
 
 pub struct IdParts {
     pub ty: Ident,
@@ -9,14 +9,22 @@ pub struct IdParts {
     pub get_fn: TokenStream,
 }
 
-fn parse_no_match(span: Span, s: impl ToTokens) -> impl ToTokens {
+fn parse_no_match(span: Span, s: impl ToTokens, available: impl ToTokens) -> TokenStream {
     quote_spanned! { span =>
-        Err(::docbot::IdParseError::NoMatch(#s.into(), <Self as ::docbot::CommandId>::names()))
+        Err(::docbot::IdParseError::NoMatch(#s.into(), #available))
     }
 }
 
-fn parse_ambiguous(span: Span, s: impl ToTokens, values: Vec<&str>) -> impl ToTokens {
-    let expected = values.into_iter().map(Literal::string);
+fn parse_ambiguous(
+    span: Span,
+    s: impl ToTokens,
+    values: Vec<&str>,
+    hidden: &[String],
+) -> impl ToTokens {
+    let expected = values
+        .into_iter()
+        .filter(|v| !hidden.iter().any(|h| h == v))
+        .map(Literal::string);
 
     quote_spanned! { span => Err(::docbot::IdParseError::Ambiguous(&[#(#expected),*], #s.into())) }
 }
@@ -24,10 +32,7 @@ fn parse_ambiguous(span: Span, s: impl ToTokens, values: Vec<&str>) -> impl ToTo
 fn parse_resolve_ambiguous<'a, 'b, T: Eq + 'b>(values: Vec<&'a (String, T)>) -> Option<&'a T> {
     let mut iter = values.into_iter();
 
-    let first = match iter.next() {
-        Some((_, ref i)) => i,
-        None => return None,
-    };
+    let (_, ref first) = iter.next()?;
 
     if iter.any(|(_, i)| i != first) {
         return None;
@@ -90,7 +95,7 @@ fn bits<'a>(
 
                 get_fn = quote_spanned! { input.span => match self { #(#id_arms),* } };
             },
-        };
+        }
 
         def = Some(quote_spanned! { input.span =>
             #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -103,58 +108,245 @@ fn bits<'a>(
     (ty, def, generics, get_fn)
 }
 
-pub fn emit(input: &InputData) -> IdParts {
-    let (ty, def, generics, get_fn) = bits(input);
+/// Returns the arms of the `to_str` match
+fn emit_to_str_arms(input: &InputData) -> Vec<TokenStream> {
+    match input.commands {
+        Commands::Struct {
+            command: Command { ref docs, .. },
+            ..
+        } => {
+            let value = Literal::string(&docs.usage.ids[0]);
 
-    let parse_s = Ident::new("__str", input.span);
-    let parse_iter = Ident::new("__iter", input.span);
+            vec![quote_spanned! { input.span => Self => #value }]
+        },
+        Commands::Enum { ref variants, .. } => variants
+            .iter()
+            .map(|CommandVariant { ident, command, .. }| {
+                let value = Literal::string(&command.docs.usage.ids[0]);
+                quote_spanned! { input.span => Self::#ident => #value }
+            })
+            .collect(),
+    }
+}
+
+/// Returns all names accepted in `locale` in trie insertion order, and the
+/// options of the command each name belongs to
+fn entry_names<'a>(
+    input: &'a InputData,
+    locale: Option<&str>,
+) -> (Vec<&'a str>, Vec<&'a CommandOpts>) {
+    let commands: Vec<_> = input.commands.iter().collect();
+
+    input
+        .commands
+        .id_entries(locale)
+        .into_iter()
+        .map(|e| (e.name, &commands[e.index].opts))
+        .unzip()
+}
+
+/// Returns the names of non-hidden commands accepted in `locale`, and the
+/// canonical ID of the command each name belongs to
+fn visible_names<'a>(input: &'a InputData, locale: Option<&str>) -> (Vec<&'a str>, Vec<&'a str>) {
+    let commands: Vec<_> = input.commands.iter().collect();
 
-    let lexer = match input.commands {
-        Commands::Struct { ref id_trie, .. } => id_trie.root().to_lexer(
+    input
+        .commands
+        .id_entries(locale)
+        .into_iter()
+        .map(|e| (e.name, commands[e.index]))
+        .filter(|(_, c)| !c.opts.hidden)
+        .map(|(n, c)| (n, &*c.docs.usage.ids[0]))
+        .unzip()
+}
+
+fn select_trie<'a, T>(
+    id_trie: &'a Trie<T>,
+    locale_tries: &'a [(String, Trie<T>)],
+    locale: Option<&str>,
+) -> &'a Trie<T> {
+    locale.map_or(id_trie, |locale| {
+        &locale_tries.iter().find(|(l, _)| l == locale).unwrap().1
+    })
+}
+
+/// Emit a lexer accepting the names for `locale`, or the base command IDs if
+/// `None`, consuming characters from `parse_iter`
+fn emit_lexer(
+    input: &InputData,
+    ty: &Ident,
+    locale: Option<&str>,
+    parse_s: &Ident,
+    parse_iter: &Ident,
+) -> TokenStream {
+    let (names, opts) = entry_names(input, locale);
+    let normalize = input.commands.set_opts().normalize;
+
+    let hidden_names: Vec<_> = names
+        .iter()
+        .zip(&opts)
+        .filter(|(_, o)| o.hidden)
+        .map(|(n, _)| normalize.apply(n, true))
+        .collect();
+
+    let (visible, _) = visible_names(input, locale);
+    let no_match = || {
+        if locale.is_some() {
+            parse_no_match(input.span, parse_s, quote_spanned! { input.span =>
+                &[#(#visible),*]
+            })
+        } else {
+            parse_no_match(input.span, parse_s, quote_spanned! { input.span =>
+                <Self as ::docbot::CommandId>::visible_names()
+            })
+        }
+    };
+
+    // Trie entries are inserted in the same order as names.  The trie only
+    // matches case-folded input, so check that the input is also a prefix of
+    // one of the IDs before case folding.
+    let case_check = |ok: TokenStream, idxs: Vec<usize>, _len: usize| {
+        if opts[idxs[0]].matching.is_case_sensitive() {
+            let cased = normalize.emit(input.span, parse_s, false);
+            let ids = idxs
+                .into_iter()
+                .map(|i| Literal::string(&normalize.apply(names[i], false)));
+            let no_match = no_match();
+
+            quote_spanned! { input.span =>
+                {
+                    let __cased = #cased;
+
+                    if #(#ids.starts_with(&*__cased))||* { #ok } else { #no_match }
+                }
+            }
+        } else {
+            ok
+        }
+    };
+
+    match input.commands {
+        Commands::Struct {
+            ref id_trie,
+            ref locale_tries,
+            ..
+        } => select_trie(id_trie, locale_tries, locale).root().to_lexer(
             input.span,
-            &parse_iter,
-            |()| quote_spanned! { input.span => Ok(#ty) },
-            || parse_no_match(input.span, &parse_s),
-            |v| parse_ambiguous(input.span, &parse_s, v),
+            parse_iter,
+            |(), idxs, len| case_check(quote_spanned! { input.span => Ok(#ty) }, idxs, len),
+            no_match,
+            |v| parse_ambiguous(input.span, parse_s, v, &hidden_names),
             parse_resolve_ambiguous,
         ),
-        Commands::Enum { ref id_trie, .. } => id_trie.root().to_lexer(
+        Commands::Enum {
+            ref id_trie,
+            ref locale_tries,
+            ..
+        } => select_trie(id_trie, locale_tries, locale).root().to_lexer(
             input.span,
-            &parse_iter,
-            |i| quote_spanned! { input.span => Ok(#ty::#i) },
-            || parse_no_match(input.span, &parse_s),
-            |v| parse_ambiguous(input.span, &parse_s, v),
+            parse_iter,
+            |i, idxs, len| case_check(quote_spanned! { input.span => Ok(#ty::#i) }, idxs, len),
+            no_match,
+            |v| parse_ambiguous(input.span, parse_s, v, &hidden_names),
             parse_resolve_ambiguous,
         ),
+    }
+}
+
+/// Emit overrides of the locale-aware `CommandId` methods, if any aliases were
+/// given
+fn emit_locales(input: &InputData, ty: &Ident, parse_s: &Ident, parse_iter: &Ident) -> TokenStream {
+    let locales: Vec<_> = match input.commands {
+        Commands::Struct {
+            ref locale_tries, ..
+        } => locale_tries.iter().map(|(l, _)| l).collect(),
+        Commands::Enum {
+            ref locale_tries, ..
+        } => locale_tries.iter().map(|(l, _)| l).collect(),
     };
 
-    let to_str_arms;
-    let names;
+    if locales.is_empty() {
+        return TokenStream::new();
+    }
 
-    match input.commands {
-        Commands::Struct {
-            command: Command { ref docs, .. },
-            ..
-        } => {
-            let value = Literal::string(&docs.usage.ids[0]);
-            to_str_arms = vec![quote_spanned! { input.span => Self => #value }];
+    let names = locales.iter().map(|l| {
+        let (names, _) = entry_names(input, Some(l));
 
-            names = docs.usage.ids.clone();
-        },
-        Commands::Enum { ref variants, .. } => {
-            to_str_arms = variants
-                .iter()
-                .map(|CommandVariant { ident, command, .. }| {
-                    let value = Literal::string(&command.docs.usage.ids[0]);
-                    quote_spanned! { input.span => Self::#ident => #value }
-                })
-                .collect();
+        quote_spanned! { input.span => &[#(#names),*] }
+    });
+    let fold = input
+        .commands
+        .set_opts()
+        .normalize
+        .emit(input.span, parse_s, true);
+    let lexers = locales.iter().map(|l| {
+        let lexer = emit_lexer(input, ty, Some(l), parse_s, parse_iter);
+
+        quote_spanned! { input.span =>
+            {
+                let __folded = #fold;
+                let mut #parse_iter = __folded.chars();
+
+                #lexer
+            }
+        }
+    });
+
+    quote_spanned! { input.span =>
+        fn names_in_locale(__locale: &str) -> &'static [&'static str] {
+            match __locale {
+                #(#locales => #names,)*
+                _ => match __locale.rsplit_once('-') {
+                    Some((__parent, _)) => Self::names_in_locale(__parent),
+                    None => Self::names(),
+                },
+            }
+        }
 
-            names = variants
-                .iter()
-                .flat_map(|v| v.command.docs.usage.ids.iter())
-                .cloned()
-                .collect();
+        fn parse_in_locale(
+            #parse_s: &str,
+            __locale: &str,
+        ) -> ::std::result::Result<Self, ::docbot::IdParseError> {
+            match __locale {
+                #(#locales => #lexers,)*
+                _ => match __locale.rsplit_once('-') {
+                    Some((__parent, _)) => Self::parse_in_locale(#parse_s, __parent),
+                    None => #parse_s.parse(),
+                },
+            }
+        }
+    }
+}
+
+pub fn emit(input: &InputData) -> IdParts {
+    let (ty, def, generics, get_fn) = bits(input);
+
+    let parse_s = Ident::new("__str", input.span);
+    let parse_iter = Ident::new("__iter", input.span);
+
+    let to_str_arms = emit_to_str_arms(input);
+    let (names, opts) = entry_names(input, None);
+    let (visible_names, canonical_names) = visible_names(input, None);
+
+    let lexer = emit_lexer(input, &ty, None, &parse_s, &parse_iter);
+    let fold = input
+        .commands
+        .set_opts()
+        .normalize
+        .emit(input.span, &parse_s, true);
+    let locales = emit_locales(input, &ty, &parse_s, &parse_iter);
+
+    let name_lit = |i: usize| (!opts[i].hidden).then(|| Literal::string(names[i]));
+    let completer = match input.commands {
+        Commands::Struct { ref id_trie, .. } => {
+            id_trie
+                .root()
+                .to_completer(input.span, &parse_iter, name_lit)
+        },
+        Commands::Enum { ref id_trie, .. } => {
+            id_trie
+                .root()
+                .to_completer(input.span, &parse_iter, name_lit)
         },
     };
 
@@ -171,8 +363,8 @@ pub fn emit(input: &InputData) -> IdParts {
             type Err = ::docbot::IdParseError;
 
             fn from_str(#parse_s: &str) -> Result<Self, Self::Err> {
-                let __to_lower = #parse_s.to_lowercase();
-                let mut #parse_iter = __to_lower.chars();
+                let __folded = #fold;
+                let mut #parse_iter = __folded.chars();
 
                 #lexer
             }
@@ -189,6 +381,19 @@ pub fn emit(input: &InputData) -> IdParts {
         impl #impl_vars ::docbot::CommandId for #ty #ty_vars #where_clause {
             fn names() -> &'static [&'static str] { &[#(#names),*] }
 
+            fn visible_names() -> &'static [&'static str] { &[#(#visible_names),*] }
+
+            fn visible_canonical_names() -> &'static [&'static str] { &[#(#canonical_names),*] }
+
+            #locales
+
+            fn complete(#parse_s: &str) -> &'static [&'static str] {
+                let __folded = #fold;
+                let mut #parse_iter = __folded.chars();
+
+                #completer
+            }
+
             fn to_str(&self) -> &'static str {
                 match self {
                     #(#to_str_arms),*
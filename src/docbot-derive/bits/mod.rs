@@ -1,3 +1,5 @@
+pub mod complete;
+pub mod dispatch;
 pub mod help;
 pub mod id;
 pub mod parse;
@@ -1,5 +1,6 @@
-use proc_macro2::TokenStream;
-use quote::quote_spanned;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote_spanned};
+use syn::Type;
 
 use super::{id::IdParts, path::PathParts};
 use crate::inputs::prelude::*;
@@ -8,21 +9,85 @@ pub struct ParseParts {
     pub items: TokenStream,
 }
 
+/// Returns an expression looking up the [`ValueHint`] of `ty` for the input
+/// string `given`, if it has one
+fn value_hint(span: Span, ty: Option<&Type>, given: &TokenStream) -> TokenStream {
+    if let Some(ty) = ty {
+        quote_spanned! { span =>
+            {
+                #[allow(unused_imports)]
+                use ::docbot::__private::{HasValueHint as _, NoValueHint as _};
+
+                (&::docbot::__private::Probe::<#ty>(::std::marker::PhantomData))
+                    .value_hint(#given)
+            }
+        }
+    } else {
+        quote_spanned! { span => None }
+    }
+}
+
+fn bad_convert(span: Span, field: &FieldInfo, id: &Ident, tok: &TokenStream) -> TokenStream {
+    let name = &field.name;
+    let hint = value_hint(
+        span,
+        field.value_type(),
+        &quote_spanned! { span => #tok.as_ref() },
+    );
+
+    quote_spanned! { span =>
+        |e| ::docbot::CommandParseError::BadConvert(
+            ::docbot::ArgumentName {
+                cmd: ::docbot::CommandId::to_str(&#id),
+                arg: #name,
+            },
+            ::docbot::Anyhow::from(e),
+            #hint,
+        )
+        .with_span(::docbot::Token::span(&#tok))
+    }
+}
+
+fn missing_required(span: Span, name: &str, id: &Ident, end: &Ident) -> TokenStream {
+    quote_spanned! { span =>
+        ::docbot::CommandParseError::MissingRequired(::docbot::ArgumentName {
+            cmd: ::docbot::CommandId::to_str(&#id),
+            arg: #name,
+        })
+        .with_span(#end.get().map(|e| e..e))
+    }
+}
+
+fn convert(span: Span, opts: &FieldOpts, tok: &TokenStream) -> TokenStream {
+    if let Some(ref path) = opts.parse_with {
+        quote_spanned! { span => #path(#tok.as_ref()) }
+    } else {
+        quote_spanned! { span => #tok.as_ref().parse() }
+    }
+}
+
 fn collect_rest(
     span: Span,
     cmd_opts: &CommandOpts,
-    field_mode: &FieldMode,
-    field_opts: &FieldOpts,
-    name: &str,
+    field: &FieldInfo,
     iter: &Ident,
     id: &Ident,
 ) -> TokenStream {
+    let FieldInfo {
+        opts: field_opts,
+        name,
+        mode: field_mode,
+        ..
+    } = field;
+
     if cmd_opts.subcommand {
         quote_spanned! { span =>
-            ::docbot::Command::parse(#iter).map_err(|e| ::docbot::CommandParseError::Subcommand(
-                ::docbot::CommandId::to_str(&#id),
-                ::std::boxed::Box::new(e),
-            ))
+            ::docbot::Command::parse_spanned(#iter).map_err(|e| {
+                ::docbot::CommandParseError::Subcommand(
+                    ::docbot::CommandId::to_str(&#id),
+                    ::std::boxed::Box::new(e),
+                )
+            })
         }
     } else if field_opts.path {
         let parse = if field_mode.required() {
@@ -30,137 +95,279 @@ fn collect_rest(
         } else {
             quote_spanned! { span => parse_opt }
         };
+        let hint = value_hint(span, field.value_type(), &quote_spanned! { span =>
+            __toks.first().map_or("", AsRef::as_ref)
+        });
 
         quote_spanned! { span =>
-            ::docbot::CommandPath::#parse(#iter).map_err(|e| {
-                ::docbot::CommandParseError::BadConvert(
-                    ::docbot::ArgumentName {
-                        cmd: ::docbot::CommandId::to_str(&#id),
-                        arg: #name
-                    },
-                    ::docbot::Anyhow::from(e)
-                )
-            })
+            {
+                let __toks: ::std::vec::Vec<_> = #iter.collect();
+                let __span = __toks
+                    .first()
+                    .and_then(::docbot::Token::span)
+                    .zip(__toks.last().and_then(::docbot::Token::span))
+                    .map(|(s, e)| s.start..e.end);
+
+                ::docbot::CommandPath::#parse(&__toks).map_err(|e| {
+                    ::docbot::CommandParseError::BadConvert(
+                        ::docbot::ArgumentName {
+                            cmd: ::docbot::CommandId::to_str(&#id),
+                            arg: #name
+                        },
+                        ::docbot::Anyhow::from(e),
+                        #hint,
+                    )
+                    .with_span(__span)
+                })
+            }
         }
     } else {
+        let tok = quote_spanned! { span => __tok };
+        let convert = convert(span, field_opts, &tok);
+        let bad_convert = bad_convert(span, field, id, &tok);
+
         quote_spanned! { span =>
             #iter
-                .map(|s| {
-                    s.as_ref().parse().map_err(|e| {
-                        ::docbot::CommandParseError::BadConvert(
-                            ::docbot::ArgumentName {
-                                cmd: ::docbot::CommandId::to_str(&#id),
-                                arg: #name,
-                            },
-                            ::docbot::Anyhow::from(e)
+                .map(|#tok| #convert.map_err(#bad_convert))
+                .collect::<::std::result::Result<_, _>>()
+        }
+    }
+}
+
+fn flag_var(span: Span, flag: &FlagArg) -> Ident {
+    format_ident!("__flag_{}", flag.name().replace('-', "_"), span = span)
+}
+
+fn scan_flags(span: Span, fields: &FieldInfos, iter: &Ident, id: &Ident) -> Option<TokenStream> {
+    let mut vars = vec![];
+    let mut arms = vec![];
+
+    for (FieldInfo { name, mode, .. }, flag) in
+        fields.iter().filter_map(|f| f.mode.flag().map(|g| (f, g)))
+    {
+        let var = flag_var(span, flag);
+        let pats = flag
+            .short
+            .iter()
+            .map(|s| Literal::string(&format!("-{s}")))
+            .chain(flag.long.iter().map(|l| Literal::string(&format!("--{l}"))));
+
+        if let FieldMode::FlagValue(..) = mode {
+            vars.push(quote_spanned! { span => let mut #var = None; });
+            arms.push(quote_spanned! { span =>
+                #(#pats)|* => {
+                    #var = Some(#iter.next().ok_or_else(|| {
+                        ::docbot::CommandParseError::MissingFlagValue(::docbot::ArgumentName {
+                            cmd: ::docbot::CommandId::to_str(&#id),
+                            arg: #name,
+                        })
+                        .with_span(::docbot::Token::span(&__tok))
+                    })?);
+                }
+            });
+        } else {
+            vars.push(quote_spanned! { span => let mut #var = false; });
+            arms.push(quote_spanned! { span => #(#pats)|* => #var = true });
+        }
+    }
+
+    if vars.is_empty() {
+        return None;
+    }
+
+    // Tokens from the start of a command path onward name subcommands, so
+    // any flags among them aren't ours to take
+    let path_pos = fields
+        .iter()
+        .filter(|f| f.mode.flag().is_none())
+        .position(|f| f.opts.path);
+    let push = if let Some(n) = path_pos {
+        quote_spanned! { span =>
+            {
+                __pos.push(__tok);
+
+                if __pos.len() > #n {
+                    __pos.extend(&mut #iter);
+                    break;
+                }
+            }
+        }
+    } else {
+        quote_spanned! { span => __pos.push(__tok) }
+    };
+
+    Some(quote_spanned! { span =>
+        #(#vars)*
+
+        let mut #iter = {
+            let mut __pos = ::std::vec::Vec::new();
+
+            while let Some(__tok) = #iter.next() {
+                match __tok.as_ref() {
+                    "--" => {
+                        __pos.extend(&mut #iter);
+                        break;
+                    },
+                    #(#arms,)*
+                    _ => #push,
+                }
+            }
+
+            __pos.into_iter().fuse()
+        };
+    })
+}
+
+fn apply_default(span: Span, field: &FieldInfo, id: &Ident, val: TokenStream) -> TokenStream {
+    match field.opts.default {
+        None => val,
+        Some(FieldDefault::Trait) => quote_spanned! { span => #val.unwrap_or_default() },
+        Some(FieldDefault::Value(ref lit)) => {
+            let name = &field.name;
+            let tok = quote_spanned! { span => __tok };
+            let convert = convert(span, &field.opts, &tok);
+
+            // An invalid default is a mistake in the command definition rather
+            // than in the input, so it isn't reported as a parse error
+            quote_spanned! { span =>
+                #val.unwrap_or_else(|| {
+                    let #tok = ::docbot::Unspanned(#lit);
+
+                    #convert.unwrap_or_else(|e| {
+                        ::std::panic!(
+                            "invalid default value {:?} for argument {:?} of command {:?}: {}",
+                            #lit,
+                            #name,
+                            ::docbot::CommandId::to_str(&#id),
+                            ::docbot::Anyhow::from(e),
                         )
                     })
                 })
-                .collect::<::std::result::Result<_, _>>()
-        }
+            }
+        },
     }
 }
 
-fn ctor_fields(
+fn process_arg(
     span: Span,
-    Command {
-        opts: cmd_opts,
-        docs,
-        fields,
-        ..
-    }: &Command,
-    path: TokenStream,
+    cmd_opts: &CommandOpts,
+    field: &FieldInfo,
     iter: &Ident,
     id: &Ident,
+    end: &Ident,
 ) -> TokenStream {
-    let process_arg = |FieldInfo {
-                           opts, name, mode, ..
-                       }: &FieldInfo| match mode {
-        FieldMode::Required => quote_spanned! { span =>
-            #iter
-                .next()
-                .ok_or_else(|| ::docbot::CommandParseError::MissingRequired(
-                    ::docbot::ArgumentName {
-                        cmd: ::docbot::CommandId::to_str(&#id),
-                        arg: #name
-                    }
-                ))?
-                .as_ref()
-                .parse()
-                .map_err(|e| ::docbot::CommandParseError::BadConvert(
-                    ::docbot::ArgumentName {
-                        cmd: ::docbot::CommandId::to_str(&#id),
-                        arg: #name,
-                    },
-                    ::docbot::Anyhow::from(e),
-                ))?
+    let FieldInfo {
+        opts, name, mode, ..
+    } = field;
+    let tok = quote_spanned! { span => __tok };
+
+    match mode {
+        FieldMode::Flag(flag) => {
+            let var = flag_var(span, flag);
+
+            quote_spanned! { span => #var }
         },
-        FieldMode::Optional => quote_spanned! { span =>
-            #iter
-                .next()
-                .map(|s| s.as_ref().parse())
-                .transpose()
-                .map_err(|e| ::docbot::CommandParseError::BadConvert(
-                    ::docbot::ArgumentName {
-                        cmd: ::docbot::CommandId::to_str(&#id),
-                        arg: #name,
-                    },
-                    ::docbot::Anyhow::from(e),
-                ))?
+        FieldMode::FlagValue(flag) => {
+            let var = flag_var(span, flag);
+            let convert = convert(span, opts, &tok);
+            let bad_convert = bad_convert(span, field, id, &tok);
+
+            apply_default(span, field, id, quote_spanned! { span =>
+                #var
+                    .map(|#tok| #convert.map_err(#bad_convert))
+                    .transpose()?
+            })
+        },
+        FieldMode::Required => {
+            let missing = missing_required(span, name, id, end);
+            let convert = convert(span, opts, &tok);
+            let bad_convert = bad_convert(span, field, id, &tok);
+
+            quote_spanned! { span =>
+                {
+                    let #tok = #iter.next().ok_or_else(|| #missing)?;
+
+                    #convert.map_err(#bad_convert)?
+                }
+            }
+        },
+        FieldMode::Optional => {
+            let convert = convert(span, opts, &tok);
+            let bad_convert = bad_convert(span, field, id, &tok);
+
+            apply_default(span, field, id, quote_spanned! { span =>
+                #iter
+                    .next()
+                    .map(|#tok| #convert.map_err(#bad_convert))
+                    .transpose()?
+            })
         },
         FieldMode::RestRequired => {
             let peekable = Ident::new("__peek", span);
-            let collected = collect_rest(span, cmd_opts, mode, opts, name, &peekable, id);
+            let collected = collect_rest(span, cmd_opts, field, &peekable, id);
+            let missing = missing_required(span, name, id, end);
 
             quote_spanned! { span =>
                 {
                     let mut #peekable = #iter.peekable();
 
-                    if let Some(..) = #peekable.peek() {
+                    if #peekable.peek().is_some() {
                         #collected
                     } else {
-                        Err(::docbot::CommandParseError::MissingRequired(
-                            ::docbot::ArgumentName {
-                                cmd: ::docbot::CommandId::to_str(&#id),
-                                arg: #name,
-                            }
-                        ))
+                        Err(#missing)
                     }
                 }?
             }
         },
         FieldMode::RestOptional => {
-            let collected = collect_rest(span, cmd_opts, mode, opts, name, iter, id);
+            let collected = collect_rest(span, cmd_opts, field, iter, id);
 
             quote_spanned! { span => #collected? }
         },
-    };
+    }
+}
 
+fn ctor_fields(
+    span: Span,
+    Command {
+        opts: cmd_opts,
+        docs,
+        fields,
+        ..
+    }: &Command,
+    path: TokenStream,
+    iter: &Ident,
+    id: &Ident,
+    end: &Ident,
+) -> TokenStream {
     let ret = match fields {
         FieldInfos::Unit => path,
         FieldInfos::Unnamed(u) => {
-            let args = u.iter().map(process_arg);
+            let args = u
+                .iter()
+                .map(|f| process_arg(span, cmd_opts, f, iter, id, end));
 
             quote_spanned! { span => #path (#(#args),*) }
         },
         FieldInfos::Named(n) => {
-            let args = n.iter().map(|(id, arg)| {
-                let arg = process_arg(arg);
+            let args = n.iter().map(|(field, arg)| {
+                let arg = process_arg(span, cmd_opts, arg, iter, id, end);
 
-                quote_spanned! { span => #id: #arg }
+                quote_spanned! { span => #field: #arg }
             });
 
             quote_spanned! { span => #path { #(#args),* } }
         },
     };
 
-    if let RestArg::None = docs.usage.rest {
+    let ret = if let RestArg::None = docs.usage.rest {
         let check = quote_spanned! { span =>
             if let Some(__trail) = #iter.next() {
                 return Err(::docbot::CommandParseError::Trailing(
-                        ::docbot::CommandId::to_str(&#id),
-                        __trail.as_ref().into(),
-                        ));
+                    ::docbot::CommandId::to_str(&#id),
+                    __trail.as_ref().into(),
+                )
+                .with_span(::docbot::Token::span(&__trail)));
             }
         };
 
@@ -173,12 +380,150 @@ fn ctor_fields(
         }
     } else {
         ret
+    };
+
+    if let Some(scan) = scan_flags(span, fields, iter, id) {
+        quote_spanned! { span =>
+            {
+                #scan
+                #ret
+            }
+        }
+    } else {
+        ret
+    }
+}
+
+/// Returns a pattern binding the subcommand of a subcommand variant to `__sub`
+fn subcommand_pat(span: Span, path: &TokenStream, cmd: &Command) -> Option<(TokenStream, Ident)> {
+    if !cmd.opts.subcommand {
+        return None;
+    }
+
+    let sub = Ident::new("__sub", span);
+    let pat = if let FieldInfos::Named(ref n) = cmd.fields {
+        let field = &n[0].0;
+
+        quote_spanned! { span => #path { #field: #sub } }
+    } else {
+        quote_spanned! { span => #path(#sub) }
+    };
+
+    Some((pat, sub))
+}
+
+fn permissions_arm(
+    span: Span,
+    path: &TokenStream,
+    pat: &TokenStream,
+    cmd: &Command,
+) -> TokenStream {
+    let perms = cmd.docs.permissions.iter().map(|p| Literal::string(p));
+
+    if let Some((pat, sub)) = subcommand_pat(span, path, cmd) {
+        quote_spanned! { span =>
+            #pat => {
+                let mut __perms = ::std::vec![#(#perms),*];
+                __perms.extend(::docbot::Command::required_permissions(#sub));
+                __perms
+            }
+        }
+    } else {
+        quote_spanned! { span => #pat => ::std::vec![#(#perms),*] }
     }
 }
 
+fn deprecated_arm(span: Span, path: &TokenStream, pat: &TokenStream, cmd: &Command) -> TokenStream {
+    let note = cmd.opts.deprecated.as_ref().map_or_else(
+        || quote_spanned! { span => None },
+        |d| {
+            let d = Literal::string(d);
+            quote_spanned! { span => Some(#d) }
+        },
+    );
+
+    if let Some((pat, sub)) = subcommand_pat(span, path, cmd) {
+        quote_spanned! { span =>
+            #pat => #note.or_else(|| ::docbot::Command::deprecated(#sub))
+        }
+    } else {
+        quote_spanned! { span => #pat => #note }
+    }
+}
+
+fn emit_arms(
+    input: &InputData,
+    arm: impl Fn(Span, &TokenStream, &TokenStream, &Command) -> TokenStream,
+) -> Vec<TokenStream> {
+    match input.commands {
+        Commands::Struct { ref command, .. } => vec![arm(
+            input.span,
+            &quote_spanned! { input.span => Self },
+            &quote_spanned! { input.span => _ },
+            command,
+        )],
+        Commands::Enum { ref variants, .. } => variants
+            .iter()
+            .map(
+                |CommandVariant {
+                     span,
+                     ident,
+                     pat,
+                     command,
+                 }| {
+                    arm(
+                        *span,
+                        &quote_spanned! { *span => Self::#ident },
+                        pat,
+                        command,
+                    )
+                },
+            )
+            .collect(),
+    }
+}
+
+fn emit_permissions(input: &InputData) -> TokenStream {
+    let arms = emit_arms(input, permissions_arm);
+
+    quote_spanned! { input.span =>
+        fn required_permissions(&self) -> ::std::vec::Vec<&'static str> {
+            match self {
+                #(#arms),*
+            }
+        }
+    }
+}
+
+fn emit_deprecated(input: &InputData) -> Option<TokenStream> {
+    let any = match input.commands {
+        Commands::Struct { ref command, .. } => {
+            command.opts.deprecated.is_some() || command.opts.subcommand
+        },
+        Commands::Enum { ref variants, .. } => variants
+            .iter()
+            .any(|v| v.command.opts.deprecated.is_some() || v.command.opts.subcommand),
+    };
+
+    if !any {
+        return None;
+    }
+
+    let arms = emit_arms(input, deprecated_arm);
+
+    Some(quote_spanned! { input.span =>
+        fn deprecated(&self) -> ::std::option::Option<&'static str> {
+            match self {
+                #(#arms),*
+            }
+        }
+    })
+}
+
 pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> ParseParts {
     let iter = Ident::new("__iter", input.span);
     let id = Ident::new("__id", input.span);
+    let end = Ident::new("__end", input.span);
     let id_ty = &id_parts.ty;
 
     let ctors: Vec<_> = match input.commands {
@@ -189,6 +534,7 @@ pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> Pa
                 quote_spanned! { input.span => Self },
                 &iter,
                 &id,
+                &end,
             );
 
             vec![quote_spanned! { input.span => #id_ty => #ctor }]
@@ -208,6 +554,7 @@ pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> Pa
                         quote_spanned! { *span => Self::#ident },
                         &iter,
                         &id,
+                        &end,
                     );
 
                     quote_spanned! { *span => #id_ty::#ident => #ctor }
@@ -216,6 +563,9 @@ pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> Pa
             .collect(),
     };
 
+    let required_permissions = emit_permissions(input);
+    let deprecated = emit_deprecated(input);
+
     // Quote variables
     let name = input.ty;
     let (impl_vars, ty_vars, where_clause) = input.generics.split_for_impl();
@@ -227,17 +577,24 @@ pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> Pa
             type Id = #id_ty;
             type Path = #path_ty;
 
-            fn parse<
-                I: IntoIterator<Item = S>,
-                S: AsRef<str>,
+            fn parse_spanned<
+                I: IntoIterator<Item = T>,
+                T: ::docbot::Token,
             >(#iter: I) -> ::std::result::Result<Self, ::docbot::CommandParseError> {
-                let mut #iter = #iter.into_iter().fuse();
+                let #end = ::std::cell::Cell::new(None);
+                let mut #iter = #iter
+                    .into_iter()
+                    .inspect(|t| {
+                        if let Some(s) = ::docbot::Token::span(t) {
+                            #end.set(Some(s.end));
+                        }
+                    })
+                    .fuse();
 
-                let #id: #id_ty = #iter
-                   .next()
-                   .ok_or(::docbot::CommandParseError::NoInput)?
-                   .as_ref()
-                   .parse()?;
+                let __tok = #iter.next().ok_or(::docbot::CommandParseError::NoInput)?;
+                let #id: #id_ty = __tok.as_ref().parse().map_err(|e| {
+                    ::docbot::CommandParseError::BadId(e).with_span(::docbot::Token::span(&__tok))
+                })?;
 
                 Ok(match #id {
                     #(#ctors),*
@@ -245,6 +602,10 @@ pub fn emit(input: &InputData, id_parts: &IdParts, path_parts: &PathParts) -> Pa
             }
 
             fn id(&self) -> #id_ty { #id_get_fn }
+
+            #required_permissions
+
+            #deprecated
         }
     };
 
@@ -5,7 +5,6 @@ use super::id::IdParts;
 use crate::inputs::prelude::*;
 
 pub struct PathParts {
-    pub is_id: bool,
     pub ty: Ident,
     pub items: TokenStream,
 }
@@ -48,7 +47,7 @@ fn handle_variant(
         ..
     }: &CommandVariant,
 ) -> (TokenStream, (TokenStream, (TokenStream, TokenStream))) {
-    let doc = Literal::string(&format!("Path for {}::{}", input_ty, ident));
+    let doc = Literal::string(&format!("Path for {input_ty}::{ident}"));
 
     let (var_pat, parse_pat, head_pat, from_id_pat) = get_pats(*span, iter, command);
 
@@ -127,7 +126,9 @@ pub fn emit(input: &InputData, id_parts: &IdParts) -> PathParts {
                     Ok(match #iter
                        .next()
                        .ok_or_else(|| {
-                           ::docbot::PathParseError::Incomplete(#id_ty::names())
+                           ::docbot::PathParseError::Incomplete(
+                               <#id_ty as ::docbot::CommandId>::visible_names(),
+                           )
                        })?
                        .as_ref()
                        .parse()? {
@@ -141,6 +142,16 @@ pub fn emit(input: &InputData, id_parts: &IdParts) -> PathParts {
             impl ::std::convert::From<#id_ty> for #ty {
                 fn from(id: #id_ty) -> Self { match id { #from_id } }
             }
+
+            impl ::docbot::ValueHint for #ty {
+                fn values() -> &'static [&'static str] {
+                    <#id_ty as ::docbot::CommandId>::visible_names()
+                }
+
+                fn canonical_values() -> &'static [&'static str] {
+                    <#id_ty as ::docbot::CommandId>::visible_canonical_names()
+                }
+            }
         });
     }
 
@@ -148,5 +159,5 @@ pub fn emit(input: &InputData, id_parts: &IdParts) -> PathParts {
         #def
     };
 
-    PathParts { is_id, ty, items }
+    PathParts { ty, items }
 }
@@ -0,0 +1,152 @@
+use proc_macro2::{Span, TokenStream};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Error,
+    Warning,
+}
+
+#[derive(Debug)]
+enum Child {
+    // Stable Rust can only report notes as part of the main message
+    #[cfg_attr(not(feature = "nightly"), allow(dead_code))]
+    Note(Span, String),
+    Help(String),
+}
+
+/// An error or warning to report at a location in the macro input
+///
+/// With the `nightly` feature enabled these are emitted using the unstable
+/// `proc_macro::Diagnostic` API.  Otherwise, errors are reported with
+/// `compile_error!`, with any notes and help folded into the message, and
+/// warnings are dropped, since stable Rust has no way for a macro to emit them.
+#[derive(Debug)]
+pub struct Diagnostic {
+    level: Level,
+    span: Span,
+    message: String,
+    children: Vec<Child>,
+}
+
+impl Diagnostic {
+    pub fn error(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Error,
+            span,
+            message: message.into(),
+            children: vec![],
+        }
+    }
+
+    pub fn warning(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Warning,
+            span,
+            message: message.into(),
+            children: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn span_note(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.children.push(Child::Note(span, message.into()));
+        self
+    }
+
+    #[must_use]
+    pub fn help(mut self, message: impl Into<String>) -> Self {
+        self.children.push(Child::Help(message.into()));
+        self
+    }
+
+    #[cfg(feature = "nightly")]
+    pub fn emit(self) -> TokenStream {
+        let level = match self.level {
+            Level::Error => proc_macro::Level::Error,
+            Level::Warning => proc_macro::Level::Warning,
+        };
+
+        self.children
+            .into_iter()
+            .fold(
+                proc_macro::Diagnostic::spanned(self.span.unwrap(), level, self.message),
+                |d, c| match c {
+                    Child::Note(s, m) => d.span_note(s.unwrap(), m),
+                    Child::Help(m) => d.help(m),
+                },
+            )
+            .emit();
+
+        TokenStream::new()
+    }
+
+    #[cfg(not(feature = "nightly"))]
+    pub fn emit(self) -> TokenStream {
+        if self.level == Level::Warning {
+            return TokenStream::new();
+        }
+
+        let span = self.span;
+        let mut message = self.message;
+
+        for child in self.children {
+            let (label, m) = match child {
+                Child::Note(_, m) => ("note", m),
+                Child::Help(m) => ("help", m),
+            };
+
+            message.push_str("\n\n");
+            message.push_str(label);
+            message.push_str(": ");
+            message.push_str(&m);
+        }
+
+        quote::quote_spanned! { span => ::core::compile_error! { #message } }
+    }
+}
+
+/// A list of errors to be reported together
+#[derive(Debug, Default)]
+pub struct Errors(Vec<Diagnostic>);
+
+impl Errors {
+    pub fn push(&mut self, err: impl Into<Errors>) { self.0.extend(err.into().0); }
+
+    /// Record the error of `res`, if any, returning its value otherwise
+    pub fn check<T>(&mut self, res: Result<T, impl Into<Errors>>) -> Option<T> {
+        res.map_err(|e| self.push(e)).ok()
+    }
+
+    /// Return `value` if no errors were recorded
+    pub fn finish<T>(self, value: T) -> Result<T, Self> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Collect the values of an iterator, recording every error encountered
+    pub fn collect<T, E: Into<Errors>>(
+        iter: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<Vec<T>, Self> {
+        let mut errs = Self::default();
+        let vals = iter.into_iter().filter_map(|r| errs.check(r)).collect();
+
+        errs.finish(vals)
+    }
+
+    pub fn emit(self) -> TokenStream { self.0.into_iter().map(Diagnostic::emit).collect() }
+}
+
+impl From<Diagnostic> for Errors {
+    fn from(diag: Diagnostic) -> Self { Self(vec![diag]) }
+}
+
+impl From<Error> for Errors {
+    fn from((err, span): Error) -> Self {
+        Diagnostic::error(span, format!("Macro execution failed:\n{err:?}")).into()
+    }
+}
@@ -1,7 +1,9 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::LazyLock,
+};
 
 use anyhow::anyhow;
-use lazy_static::lazy_static;
 use proc_macro2::Span;
 use regex::{Regex, RegexBuilder};
 
@@ -14,13 +16,32 @@ pub enum RestArg {
     Required(String),
 }
 
+#[derive(Clone, Debug)]
+pub struct FlagArg {
+    pub short: Option<String>,
+    pub long: Option<String>,
+    pub value: Option<String>,
+}
+
+impl FlagArg {
+    pub fn name(&self) -> &str {
+        self.long
+            .as_deref()
+            .or(self.short.as_deref())
+            .unwrap_or_else(|| unreachable!())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CommandUsage {
     pub ids: Vec<String>,
+    pub flags: Vec<FlagArg>,
     pub required: Vec<String>,
     pub optional: Vec<String>,
     pub rest: RestArg,
     pub desc: String,
+    /// The span of the doc comment line declaring each argument
+    pub arg_spans: HashMap<String, Span>,
 }
 
 #[derive(Debug)]
@@ -30,6 +51,7 @@ pub struct CommandDocs {
     pub summary: Option<String>,
     pub args: Vec<(String, bool, String)>,
     pub examples: Option<String>,
+    pub permissions: Vec<String>,
 }
 
 pub struct CommandSetDocs {
@@ -43,12 +65,37 @@ pub trait ParseDocs: Sized {
     fn no_docs() -> Result<Self, anyhow::Error>;
 }
 
+/// A paragraph of a doc comment, tracking the span of each line
+struct Paragraph {
+    text: String,
+    /// The byte offset into `text` and span of each line
+    lines: Vec<(usize, Span)>,
+}
+
+impl Paragraph {
+    fn span(&self) -> Span { self.lines[0].1 }
+
+    /// Get the span of the line containing the given byte offset into the text
+    fn span_at(&self, pos: usize) -> Span {
+        let i = self.lines.partition_point(|(start, _)| *start <= pos);
+
+        self.lines[i.saturating_sub(1)].1
+    }
+}
+
+/// Join a list of spans, falling back to the first if joining is unsupported
+fn join_spans(spans: impl IntoIterator<Item = Span>) -> Option<Span> {
+    spans
+        .into_iter()
+        .reduce(|prev, curr| prev.join(curr).unwrap_or(prev))
+}
+
 fn take_paragraph<I: Iterator<Item = (String, Span)>>(
     docs: &mut I,
     preserve_lines: bool,
-) -> Option<(String, Span)> {
-    let mut ret = String::new();
-    let mut ret_span = None;
+) -> Option<Paragraph> {
+    let mut text = String::new();
+    let mut lines = vec![];
 
     for (string, span) in docs {
         let trimmed = string.trim();
@@ -58,53 +105,85 @@ fn take_paragraph<I: Iterator<Item = (String, Span)>>(
         }
 
         if preserve_lines {
-            ret.push_str(string.as_ref());
-            ret.push('\n');
+            lines.push((text.len(), span));
+            text.push_str(string.as_ref());
+            text.push('\n');
         } else {
-            if !ret.is_empty() {
-                ret.push(' ');
+            if !text.is_empty() {
+                text.push(' ');
             }
 
-            ret.push_str(trimmed);
+            lines.push((text.len(), span));
+            text.push_str(trimmed);
         }
-
-        ret_span = Some(ret_span.map_or(span, |r: Span| r.join(span).unwrap()));
     }
 
-    ret_span.map(|s| (ret, s))
+    if lines.is_empty() {
+        None
+    } else {
+        Some(Paragraph { text, lines })
+    }
 }
 
-fn parse_usage_desc((par, span): (String, Span)) -> Result<CommandUsage> {
-    lazy_static! {
-        static ref USAGE_SPLIT_RE: Regex =
-            Regex::new(r"^\s*`((?:[^`]|``)*)`(?:\s*:)?\s*(.*)\s*$").unwrap();
-        static ref COMMAND_IDS_RE: Regex =
-            Regex::new(r"^\s*(?:([^\(]\S*)|\(\s*([^\)]*)\))").unwrap();
-        static ref PIPE_RE: Regex = Regex::new(r"\s*\|\s*").unwrap();
-        static ref REQUIRED_ARG_RE: Regex =
-            Regex::new(r"^\s*<([^>]{0,2}|[^>]*[^>\.]{3})>").unwrap();
-        static ref OPTIONAL_ARG_RE: Regex =
-            Regex::new(r"^\s*\[([^\]]{0,2}|[^\]]*[^\]\.]{3})\]").unwrap();
-        static ref REST_ARG_RE: Regex =
-            Regex::new(r"^\s*(?:<([^>]+)...>|\[([^\]]+)...\])").unwrap();
-        static ref TRAILING_RE: Regex = Regex::new(r"\S").unwrap();
+fn check_arg_names(arg_spans: &[(String, Span)]) -> Result<()> {
+    let mut names = HashSet::new();
+
+    for (name, span) in arg_spans {
+        if name.starts_with('-') {
+            return Err((
+                anyhow!(
+                    "unexpected flag {:?}, flags must precede positional arguments",
+                    name
+                ),
+                *span,
+            ));
+        }
+
+        if !names.insert(name) {
+            return Err((anyhow!("duplicate argument name {:?}", name), *span));
+        }
     }
 
-    let (mut usage, desc) = {
-        let caps = USAGE_SPLIT_RE.captures(&par).ok_or_else(|| {
+    Ok(())
+}
+
+fn parse_usage_desc(par: &Paragraph) -> Result<CommandUsage> {
+    static USAGE_SPLIT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*`((?:[^`]|``)*)`(?:\s*:)?\s*(.*)\s*$").unwrap());
+    static COMMAND_IDS_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*(?:([^\(]\S*)|\(\s*([^\)]*)\))").unwrap());
+    static PIPE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\|\s*").unwrap());
+    static FLAG_ARG_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(concat!(
+            r"^\s*\[\s*(?:-([^\s\-\|\]<])(?:\s*\|\s*--([^\s\|\]<]+))?|--([^\s\|\]<]+))",
+            r"(?:\s*<([^>]+)>)?\s*\]",
+        ))
+        .unwrap()
+    });
+    static REQUIRED_ARG_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*<([^>]{0,2}|[^>]*[^>\.]{3})>").unwrap());
+    static OPTIONAL_ARG_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*\[([^\]]{0,2}|[^\]]*[^\]\.]{3})\]").unwrap());
+    static REST_ARG_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*(?:<([^>]+)...>|\[([^\]]+)...\])").unwrap());
+    static TRAILING_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\S").unwrap());
+
+    let (mut usage, mut pos, desc) = {
+        let caps = USAGE_SPLIT_RE.captures(&par.text).ok_or_else(|| {
             (
                 anyhow!("Invalid usage paragraph, format should be `<usage>` <description>"),
-                span,
+                par.span(),
             )
         })?;
+        let usage = caps.get(1).unwrap();
 
-        (caps.get(1).unwrap().as_str(), caps[2].to_owned())
+        (usage.as_str(), usage.start(), caps[2].to_owned())
     };
 
     let ids_match = COMMAND_IDS_RE.captures(usage).ok_or_else(|| {
         (
             anyhow!("invalid command ID specifier, expected e.g. 'foo' or '(foo|bar)'"),
-            span,
+            par.span_at(pos),
         )
     })?;
 
@@ -114,70 +193,110 @@ fn parse_usage_desc((par, span): (String, Span)) -> Result<CommandUsage> {
         vec![ids_match[1].into()]
     };
 
-    usage = &usage[ids_match.get(0).unwrap().end()..];
+    // Advance past a matched token, returning the span of the line it began on
+    let mut advance = |usage: &mut &str, end: usize| {
+        let span = par.span_at(pos + usage.len() - usage.trim_start().len());
+
+        *usage = &usage[end..];
+        pos += end;
+
+        span
+    };
+
+    advance(&mut usage, ids_match.get(0).unwrap().end());
+
+    let mut arg_spans = vec![];
+
+    let mut flags = vec![];
+    while let Some(caps) = FLAG_ARG_RE.captures(usage) {
+        let flag = FlagArg {
+            short: caps.get(1).map(|m| m.as_str().into()),
+            long: caps.get(2).or(caps.get(3)).map(|m| m.as_str().into()),
+            value: caps.get(4).map(|m| m.as_str().into()),
+        };
+
+        arg_spans.push((
+            flag.name().to_owned(),
+            advance(&mut usage, caps.get(0).unwrap().end()),
+        ));
+        flags.push(flag);
+    }
 
     let mut required = vec![];
     while let Some(req) = REQUIRED_ARG_RE.captures(usage) {
-        required.push(req[1].into());
+        let name: String = req[1].into();
 
-        usage = &usage[req.get(0).unwrap().end()..];
+        arg_spans.push((name.clone(), advance(&mut usage, req.get(0).unwrap().end())));
+        required.push(name);
     }
 
     let mut optional = vec![];
     while let Some(opt) = OPTIONAL_ARG_RE.captures(usage) {
-        optional.push(opt[1].into());
+        let name: String = opt[1].into();
 
-        usage = &usage[opt.get(0).unwrap().end()..];
+        arg_spans.push((name.clone(), advance(&mut usage, opt.get(0).unwrap().end())));
+        optional.push(name);
     }
 
-    let rest = REST_ARG_RE.captures(usage).map_or(RestArg::None, |rest| {
-        usage = &usage[rest.get(0).unwrap().end()..];
+    let rest = REST_ARG_RE.captures(usage).map_or(RestArg::None, |caps| {
+        let name = caps.get(2).or_else(|| caps.get(1)).unwrap().as_str();
 
-        rest.get(2).map_or_else(
-            || RestArg::Required(rest[1].into()),
-            |cap| RestArg::Optional(cap.as_str().into()),
-        )
+        arg_spans.push((name.into(), advance(&mut usage, caps.get(0).unwrap().end())));
+
+        if caps.get(2).is_some() {
+            RestArg::Optional(name.into())
+        } else {
+            RestArg::Required(name.into())
+        }
     });
 
-    if TRAILING_RE.is_match(usage) {
-        return Err((anyhow!("trailing string {:?}", usage), span));
+    if let Some(trailing) = TRAILING_RE.find(usage) {
+        return Err((
+            anyhow!("trailing string {:?}", usage),
+            par.span_at(pos + trailing.start()),
+        ));
     }
 
+    check_arg_names(&arg_spans)?;
+
     Ok(CommandUsage {
         ids,
+        flags,
         required,
         optional,
         rest,
         desc,
+        arg_spans: arg_spans.into_iter().collect(),
     })
 }
 
 fn relax_lines(s: impl AsRef<str>) -> String {
-    lazy_static! {
-        static ref LINE_RE: Regex = Regex::new(r"\s*\n\s*").unwrap();
-    }
+    static LINE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\n\s*").unwrap());
 
     LINE_RE.replace_all(s.as_ref().trim(), " ").into_owned()
 }
 
+/// Parse the argument descriptions in the text of `par` following the byte
+/// offset `start`, or an empty list if `par` is `None`
 fn parse_argument_lines(
-    span: Span,
+    par: Option<(&Paragraph, usize)>,
     usage: &CommandUsage,
-    s: impl AsRef<str>,
+    usage_span: Span,
 ) -> Result<Vec<(String, bool, String)>> {
-    lazy_static! {
-        static ref ARGUMENT_RE: Regex =
-            RegexBuilder::new(r"^\s*([^\n\s](?:[^:\n]*[^:\n\s])?)\s*:\s*")
-                .multi_line(true)
-                .build()
-                .unwrap();
-    }
+    static ARGUMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+        RegexBuilder::new(r"^\s*([^\n\s](?:[^:\n]*[^:\n\s])?)\s*:\s*")
+            .multi_line(true)
+            .build()
+            .unwrap()
+    });
 
-    let s = s.as_ref();
+    let (s, start) = par.map_or(("", 0), |(p, start)| (&p.text[start..], start));
+    let span_at = |pos: usize| par.map_or(usage_span, |(p, _)| p.span_at(start + pos));
     let mut args = HashMap::new();
     let matches: Vec<_> = ARGUMENT_RE.captures_iter(s).collect();
 
     for (i, caps) in matches.iter().enumerate() {
+        let pos = caps.get(0).unwrap().start();
         let end = matches
             .get(i + 1)
             .map_or_else(|| s.len(), |c| c.get(0).unwrap().start());
@@ -186,59 +305,73 @@ fn parse_argument_lines(
             Entry::Occupied(o) => {
                 return Err((
                     anyhow!("duplicate argument description {:?}", o.key()),
-                    span,
+                    span_at(pos),
                 ));
             },
             Entry::Vacant(v) => {
-                v.insert(relax_lines(&s[caps.get(0).unwrap().end()..end]));
+                v.insert((
+                    relax_lines(&s[caps.get(0).unwrap().end()..end]),
+                    span_at(pos),
+                ));
             },
         }
     }
 
     if !s.is_empty() && args.is_empty() {
-        return Err((anyhow!("unexpected argument description format"), span));
+        return Err((
+            anyhow!("unexpected argument description format"),
+            span_at(0),
+        ));
     }
 
     let expected_args: Vec<_> = usage
-        .required
+        .flags
         .iter()
-        .map(|a| (a, true))
-        .chain(usage.optional.iter().map(|a| (a, false)))
+        .map(|f| (f.name(), false))
+        .chain(usage.required.iter().map(|a| (a.as_str(), true)))
+        .chain(usage.optional.iter().map(|a| (a.as_str(), false)))
         .chain(match usage.rest {
             RestArg::None => None,
-            RestArg::Optional(ref a) => Some((a, false)),
-            RestArg::Required(ref a) => Some((a, true)),
+            RestArg::Optional(ref a) => Some((a.as_str(), false)),
+            RestArg::Required(ref a) => Some((a.as_str(), true)),
         })
         .collect();
 
     for (arg, _) in &expected_args {
-        if args.get(*arg).is_none() {
+        if !args.contains_key(*arg) {
+            let mut have: Vec<_> = args.keys().collect();
+            have.sort_unstable();
+
             return Err((
                 anyhow!(
                     "missing documentation for argument {:?} (have documentation for {:?})",
                     arg,
-                    args.keys().collect::<Vec<_>>(),
+                    have,
                 ),
-                span,
+                usage.arg_spans.get(*arg).copied().unwrap_or(usage_span),
             ));
         }
     }
 
-    if args.len() != expected_args.len() {
+    if let Some((arg, (_, span))) = args
+        .iter()
+        .find(|(a, _)| !expected_args.iter().any(|(e, _)| e == a))
+    {
         return Err((
             anyhow!(
-                "mismatched number of argument descriptions (expected {}, got {})",
+                "documentation for argument {:?} not present in usage (expected {}, got {})",
+                arg,
                 expected_args.len(),
                 args.len()
             ),
-            span,
+            *span,
         ));
     }
 
     let args = expected_args
         .into_iter()
         .map(|(arg, req)| {
-            let (key, val) = args.remove_entry(arg).unwrap();
+            let (key, (val, _)) = args.remove_entry(arg).unwrap();
             (key, req, val)
         })
         .collect();
@@ -248,35 +381,32 @@ fn parse_argument_lines(
 
 impl ParseDocs for CommandDocs {
     fn parse_docs(docs: Vec<(String, Span)>, fallback_span: Span) -> Result<Self> {
-        let span = docs
-            .iter()
-            .map(|(_, s)| s)
-            .fold(None, |prev, curr| match prev {
-                None => Some(Some(*curr)),
-                Some(p) => Some(p.and_then(|p| p.join(*curr))),
-            })
-            .flatten()
-            .unwrap_or(fallback_span);
+        let span = join_spans(docs.iter().map(|(_, s)| *s)).unwrap_or(fallback_span);
 
         let mut docs = docs.into_iter();
 
-        let usage_desc =
-            take_paragraph(&mut docs, false).unwrap_or_else(|| (String::new(), fallback_span));
-        let usage = parse_usage_desc(usage_desc)?;
+        let usage_par = take_paragraph(&mut docs, false).unwrap_or_else(|| Paragraph {
+            text: String::new(),
+            lines: vec![(0, fallback_span)],
+        });
+        let usage = parse_usage_desc(&usage_par)?;
 
         let mut summary = None;
-        let mut args = None;
         let mut examples = None;
+        let mut permissions = None;
 
-        while let Some((par, span)) = take_paragraph(&mut docs, true) {
-            lazy_static! {
-                static ref HEADER_RE: Regex = Regex::new(r"^\s*#\s*(\S+)\s*\n").unwrap();
-            }
+        let mut args_par = None;
 
+        while let Some(par) = take_paragraph(&mut docs, true) {
+            static HEADER_RE: LazyLock<Regex> =
+                LazyLock::new(|| Regex::new(r"^\s*#\s*(\S+)\s*\n").unwrap());
+
+            let span = par.span();
             let header_caps = HEADER_RE
-                .captures(&par)
+                .captures(&par.text)
                 .ok_or_else(|| (anyhow!("paragraph missing header"), span))?;
-            let rest = &par[header_caps.get(0).unwrap().end()..];
+            let start = header_caps.get(0).unwrap().end();
+            let rest = &par.text[start..];
 
             match header_caps[1].to_lowercase().as_ref() {
                 "description" | "overview" | "summary" => {
@@ -287,11 +417,11 @@ impl ParseDocs for CommandDocs {
                     summary = Some(relax_lines(rest));
                 },
                 "arguments" | "parameters" => {
-                    if args.is_some() {
+                    if args_par.is_some() {
                         return Err((anyhow!("multiple arguments sections found"), span));
                     }
 
-                    args = Some(parse_argument_lines(span, &usage, rest)?);
+                    args_par = Some((par, start));
                 },
                 "examples" => {
                     if examples.is_some() {
@@ -300,15 +430,32 @@ impl ParseDocs for CommandDocs {
 
                     examples = Some(relax_lines(rest));
                 },
+                "permissions" | "requires" => {
+                    if permissions.is_some() {
+                        return Err((anyhow!("multiple permissions sections found"), span));
+                    }
+
+                    permissions = Some(
+                        rest.split([',', '\n'])
+                            .map(str::trim)
+                            .filter(|p| !p.is_empty())
+                            .map(Into::into)
+                            .collect(),
+                    );
+                },
                 _ => (),
             }
         }
 
         if usage.desc.trim().is_empty() {
-            return Err((anyhow!("missing command description"), span));
+            return Err((anyhow!("missing command description"), usage_par.span()));
         }
 
-        let args = args.map_or_else(|| parse_argument_lines(span, &usage, ""), Ok)?;
+        let args = parse_argument_lines(
+            args_par.as_ref().map(|(p, s)| (p, *s)),
+            &usage,
+            usage_par.span(),
+        )?;
 
         Ok(Self {
             span,
@@ -316,6 +463,7 @@ impl ParseDocs for CommandDocs {
             summary,
             args,
             examples,
+            permissions: permissions.unwrap_or_default(),
         })
     }
 
@@ -324,27 +472,17 @@ impl ParseDocs for CommandDocs {
 
 impl ParseDocs for CommandSetDocs {
     fn parse_docs(docs: Vec<(String, Span)>, fallback_span: Span) -> Result<Self> {
-        let span = docs
-            .iter()
-            .map(|(_, s)| s)
-            .fold(None, |prev, curr| match prev {
-                None => Some(Some(*curr)),
-                Some(p) => Some(p.and_then(|p| p.join(*curr))),
-            })
-            .flatten()
-            .unwrap_or(fallback_span);
+        let span = join_spans(docs.iter().map(|(_, s)| *s)).unwrap_or(fallback_span);
 
         let mut docs = docs.into_iter();
         let mut summary = String::new();
 
-        while let Some((par, span)) = take_paragraph(&mut docs, false) {
+        while let Some(par) = take_paragraph(&mut docs, false) {
             if !summary.is_empty() {
                 summary.push('\n');
             }
 
-            summary.push_str(&par);
-
-            let _ = span; // TODO: here just in case
+            summary.push_str(&par.text);
         }
 
         let summary = summary.trim();
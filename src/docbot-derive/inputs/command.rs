@@ -1,13 +1,23 @@
 use anyhow::anyhow;
 use proc_macro2::TokenStream;
 use quote::quote_spanned;
-use syn::{spanned::Spanned, Attribute, Data, DeriveInput, Fields};
+use syn::{spanned::Spanned, Attribute, Data, DeriveInput, Fields, Variant};
 
 use super::prelude::*;
-use crate::{attrs, trie::Trie, Result};
+use crate::{
+    attrs,
+    diag::{Diagnostic, Errors},
+    normalize::Normalization,
+    trie::{DuplicateKey, Trie},
+    Result,
+};
 
 #[allow(clippy::manual_non_exhaustive)]
 pub struct Command<'a> {
+    pub span: Span,
+    /// The ID of the Fluent-style message used to localize this command's
+    /// documentation
+    pub key: String,
     pub opts: CommandOpts,
     pub docs: CommandDocs,
     pub fields: FieldInfos<'a>,
@@ -15,9 +25,22 @@ pub struct Command<'a> {
 }
 
 impl<'a> Command<'a> {
-    pub fn new(span: Span, attrs: &[Attribute], fields: &'a Fields) -> Result<Self> {
-        let (opts, docs) = attrs::parse_command(attrs, span)?;
+    pub fn new(
+        span: Span,
+        key: String,
+        attrs: &[Attribute],
+        fields: &'a Fields,
+    ) -> Result<Self, Errors> {
+        let (opts, mut docs) = attrs::parse_command(attrs, span)?;
+
+        for perm in &opts.requires {
+            if !docs.permissions.contains(perm) {
+                docs.permissions.push(perm.clone());
+            }
+        }
+
         let fields = FieldInfos::new(span, &docs.usage, fields)?;
+        let mut errs = Errors::default();
 
         if opts.subcommand {
             let is_valid = {
@@ -31,7 +54,7 @@ impl<'a> Command<'a> {
             };
 
             if !is_valid {
-                return Err((
+                errs.push((
                     anyhow!(
                         "Invalid structure for a subcommand, should be a single rest parameter"
                     ),
@@ -42,14 +65,16 @@ impl<'a> Command<'a> {
 
         for field in fields.iter() {
             if field.opts.path && !field.mode.rest() {
-                return Err((
+                errs.push((
                     anyhow!("Invalid path argument, should be a rest parameter"),
                     field.span,
                 ));
             }
         }
 
-        Ok(Self {
+        errs.finish(Self {
+            span,
+            key,
             opts,
             docs,
             fields,
@@ -65,86 +90,306 @@ pub struct CommandVariant<'a> {
     pub command: Command<'a>,
 }
 
+impl<'a> CommandVariant<'a> {
+    fn new(variant: &'a Variant, matching: MatchOpts, set_key: &str) -> Result<Self, Errors> {
+        let span = variant.span();
+        let key = format!("{}-{}", set_key, message_id(&variant.ident));
+        let mut command = Command::new(span, key, &variant.attrs, &variant.fields)?;
+
+        if !command.opts.set.is_empty() {
+            return Err((
+                anyhow!(
+                    "dispatch and normalization options must be placed on the command set type"
+                ),
+                span,
+            )
+                .into());
+        }
+
+        command.opts.matching = command.opts.matching.or(matching);
+
+        let id = &variant.ident;
+        let pat = match variant.fields {
+            Fields::Named(..) => quote_spanned! { span => Self::#id { .. } },
+            Fields::Unnamed(..) => quote_spanned! { span => Self::#id(..) },
+            Fields::Unit => quote_spanned! { span => Self::#id },
+        };
+
+        Ok(Self {
+            span,
+            ident: id,
+            pat,
+            command,
+        })
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
 pub enum Commands<'a> {
     Struct {
         id_trie: Trie<()>,
+        locale_tries: Vec<(String, Trie<()>)>,
         command: Command<'a>,
     },
     Enum {
+        key: String,
+        opts: CommandSetOpts,
         docs: CommandSetDocs,
         id_trie: Trie<&'a Ident>,
+        locale_tries: Vec<(String, Trie<&'a Ident>)>,
         variants: Vec<CommandVariant<'a>>,
     },
 }
 
+/// Convert a type or variant name to kebab case for use as a message ID
+fn message_id(ident: &Ident) -> String {
+    let mut ret = String::new();
+    let mut prev_lower = false;
+
+    for c in ident.to_string().chars() {
+        if c.is_uppercase() && prev_lower {
+            ret.push('-');
+        }
+
+        prev_lower = c.is_lowercase() || c.is_numeric();
+        ret.extend(if c == '_' { '-' } else { c }.to_lowercase());
+    }
+
+    ret
+}
+
+/// A name accepted by an ID lexer
+pub struct IdEntry<'b> {
+    pub name: &'b str,
+    /// The index of the command this name belongs to
+    pub index: usize,
+    pub span: Span,
+    /// Whether this is a localized alias rather than an ID from the usage line
+    pub is_alias: bool,
+}
+
+/// List the IDs of each command, followed by their aliases for `locale` if one
+/// is given, in trie insertion order
+fn id_entries<'b>(commands: &[&'b Command], locale: Option<&str>) -> Vec<IdEntry<'b>> {
+    let ids = commands.iter().enumerate().flat_map(|(index, cmd)| {
+        cmd.docs.usage.ids.iter().map(move |name| IdEntry {
+            name,
+            index,
+            span: cmd.span,
+            is_alias: false,
+        })
+    });
+
+    let aliases = commands.iter().enumerate().flat_map(move |(index, cmd)| {
+        cmd.opts
+            .aliases
+            .iter()
+            .filter(move |a| Some(&*a.locale) == locale)
+            .map(move |a| IdEntry {
+                name: &a.name,
+                index,
+                span: a.span,
+                is_alias: true,
+            })
+    });
+
+    ids.chain(aliases).collect()
+}
+
+/// Build the ID trie for `locale`, or the base ID trie if `None`
+fn build_trie<T>(
+    commands: &[&Command],
+    normalize: Normalization,
+    locale: Option<&str>,
+    payload: impl Fn(usize) -> T,
+) -> Result<Trie<T>, Diagnostic> {
+    let entries = id_entries(commands, locale);
+
+    Trie::new(entries.iter().map(|e| {
+        let key = normalize.apply(e.name, true);
+        let opts = &commands[e.index].opts;
+        // Hidden commands shouldn't be discoverable by guessing a prefix
+        let min_len = if opts.hidden {
+            key.chars().count()
+        } else {
+            opts.matching.min_len(&key)
+        };
+
+        (key, payload(e.index), min_len)
+    }))
+    .map_err(|DuplicateKey { key, first, second }| {
+        let msg = match locale {
+            Some(locale) => format!("duplicate command ID {key:?} in locale {locale:?}"),
+            None => format!("duplicate command ID {key:?}"),
+        };
+
+        Diagnostic::error(entries[second].span, msg)
+            .span_note(entries[first].span, format!("{key:?} first used here"))
+    })
+}
+
+/// Build the base ID trie, as well as one for each locale given an alias by
+/// any command
+#[allow(clippy::type_complexity)]
+fn build_tries<T>(
+    commands: &[&Command],
+    normalize: Normalization,
+    payload: impl Fn(usize) -> T + Copy,
+) -> Result<(Trie<T>, Vec<(String, Trie<T>)>), Errors> {
+    let mut locales: Vec<&str> = vec![];
+
+    for alias in commands.iter().flat_map(|c| &c.opts.aliases) {
+        if !locales.contains(&&*alias.locale) {
+            locales.push(&alias.locale);
+        }
+    }
+
+    let mut errs = Errors::default();
+    let id_trie = errs.check(build_trie(commands, normalize, None, payload));
+    let locale_tries = errs.check(Errors::collect(locales.into_iter().map(|l| {
+        build_trie(commands, normalize, Some(l), payload).map(|t| (l.to_owned(), t))
+    })));
+
+    // Both of these are present if no errors were recorded
+    Ok(errs.finish(id_trie.zip(locale_tries))?.unwrap())
+}
+
 impl<'a> Commands<'a> {
-    pub fn new(input: &'a DeriveInput) -> Result<Self> {
+    pub fn new(input: &'a DeriveInput) -> Result<Self, Errors> {
+        let key = message_id(&input.ident);
+
         Ok(match input.data {
             Data::Struct(ref s) => {
-                let command = Command::new(input.span(), &input.attrs, &s.fields)?;
+                let command = Command::new(input.span(), key, &input.attrs, &s.fields)?;
+                let (id_trie, locale_tries) =
+                    build_tries(&[&command], command.opts.set.normalize, |_| ())?;
 
-                let id_trie = Trie::new(
-                    command
-                        .docs
-                        .usage
-                        .ids
-                        .iter()
-                        .map(|i| (i.to_lowercase(), ())),
-                )
-                .map_err(|e| (e.context("failed to construct command lexer"), input.span()))?;
-
-                Self::Struct { id_trie, command }
+                Self::Struct {
+                    id_trie,
+                    locale_tries,
+                    command,
+                }
             },
             Data::Enum(ref e) => {
-                let docs = attrs::parse_enum(&input.attrs, input.span())?;
+                let mut errs = Errors::default();
 
-                let variants = e
-                    .variants
-                    .iter()
-                    .map(|v| {
-                        Ok(CommandVariant {
-                            ident: &v.ident,
-                            pat: {
-                                let id = &v.ident;
-                                match v.fields {
-                                    Fields::Named(..) => {
-                                        quote_spanned! { v.span() => Self::#id { .. } }
-                                    },
-                                    Fields::Unnamed(..) => {
-                                        quote_spanned! { v.span() => Self::#id(..) }
-                                    },
-                                    Fields::Unit => quote_spanned! { v.span() => Self::#id },
-                                }
-                            },
-                            command: Command::new(v.span(), &v.attrs, &v.fields)?,
-                            span: v.span(),
-                        })
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-
-                let id_trie = Trie::new(variants.iter().flat_map(|v| {
-                    v.command
-                        .docs
-                        .usage
-                        .ids
+                let set = errs.check(attrs::parse_enum(&input.attrs, input.span()));
+                let matching = set
+                    .as_ref()
+                    .map_or_else(MatchOpts::default, |(o, _)| o.matching);
+                let variants = errs.check(Errors::collect(
+                    e.variants
                         .iter()
-                        .map(move |i| (i.to_lowercase(), v.ident))
-                }))
-                .map_err(|e| (e.context("failed to construct command lexer"), input.span()))?;
+                        .map(|v| CommandVariant::new(v, matching, &key)),
+                ));
+
+                // Both of these are present if no errors were recorded
+                let ((opts, docs), variants) = errs.finish(set.zip(variants))?.unwrap();
+
+                let commands: Vec<_> = variants.iter().map(|v| &v.command).collect();
+                let (id_trie, locale_tries) =
+                    build_tries(&commands, opts.normalize, |i| variants[i].ident)?;
 
                 Commands::Enum {
+                    key,
+                    opts,
                     docs,
                     id_trie,
+                    locale_tries,
                     variants,
                 }
             },
             Data::Union(_) => {
-                return Err((anyhow!("cannot derive Docbot on a union."), input.span()));
+                return Err((anyhow!("cannot derive Docbot on a union."), input.span()).into());
             },
         })
     }
 
-    pub fn iter(&self) -> Iter {
+    /// List the names accepted by the ID lexer for `locale`, or the base
+    /// lexer if `None`, in trie insertion order
+    pub fn id_entries(&self, locale: Option<&str>) -> Vec<IdEntry<'_>> {
+        id_entries(&self.iter().collect::<Vec<_>>(), locale)
+    }
+
+    /// Warn about IDs that shadow the IDs of other commands, unless
+    /// acknowledged with `#[docbot(allow_prefix)]`
+    pub fn warnings(&self) -> Vec<Diagnostic> {
+        let (id_trie, locale_tries, variants) = match self {
+            Self::Struct { .. } => return vec![],
+            Self::Enum {
+                id_trie,
+                locale_tries,
+                variants,
+                ..
+            } => (id_trie, locale_tries, variants),
+        };
+
+        let allowed = |id: &Ident| {
+            variants
+                .iter()
+                .any(|v| v.ident == id && v.command.opts.matching.allows_prefix())
+        };
+        let hidden = |id: &Ident| {
+            variants
+                .iter()
+                .any(|v| v.ident == id && v.command.opts.hidden)
+        };
+
+        std::iter::once((None, id_trie))
+            .chain(locale_tries.iter().map(|(l, t)| (Some(&**l), t)))
+            .flat_map(|(locale, trie)| {
+                let entries = self.id_entries(locale);
+
+                trie.strict_prefixes()
+                    .iter()
+                    .filter_map(move |&(short, long)| {
+                        let (short_id, short_var) = trie.payload(short);
+                        let (long_id, long_var) = trie.payload(long);
+
+                        // Pairs of unlocalized IDs are reported for the base
+                        // trie only
+                        if short_var == long_var
+                            || allowed(short_var)
+                            || allowed(long_var)
+                            || hidden(long_var)
+                            || locale.is_some()
+                                && !(entries[short].is_alias || entries[long].is_alias)
+                        {
+                            return None;
+                        }
+
+                        Some(
+                            Diagnostic::warning(
+                                entries[long].span,
+                                format!(
+                                    "command ID {:?} is a strict prefix of {:?}, so {:?} cannot \
+                                     be abbreviated to fewer than {} characters",
+                                    short_id,
+                                    long_id,
+                                    long_id,
+                                    short_id.chars().count() + 1,
+                                ),
+                            )
+                            .span_note(entries[short].span, format!("{short_id:?} is defined here"))
+                            .help(
+                                "add #[docbot(allow_prefix)] to either command if this is \
+                                 intentional",
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn set_opts(&self) -> &CommandSetOpts {
+        match self {
+            Self::Struct { command, .. } => &command.opts.set,
+            Self::Enum { opts, .. } => opts,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
         match self {
             Self::Struct { command, .. } => Iter::Struct(std::iter::once(command)),
             Self::Enum { variants, .. } => Iter::Enum(variants.iter()),
@@ -179,4 +424,4 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
-impl<'a> ExactSizeIterator for Iter<'a> {}
+impl ExactSizeIterator for Iter<'_> {}
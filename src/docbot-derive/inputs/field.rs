@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
 use anyhow::anyhow;
-use syn::{spanned::Spanned, Fields, Type};
+use quote::ToTokens;
+use syn::{spanned::Spanned, Fields, GenericArgument, PathArguments, Type, TypePath};
 
 use super::prelude::*;
-use crate::{attrs, Result};
+use crate::{attrs, diag::Errors, Result};
 
 pub enum FieldMode {
+    Flag(FlagArg),
+    FlagValue(FlagArg),
     Required,
     Optional,
     RestRequired,
@@ -17,6 +20,13 @@ impl FieldMode {
     pub fn required(&self) -> bool { matches!(self, Self::Required | Self::RestRequired) }
 
     pub fn rest(&self) -> bool { matches!(self, Self::RestRequired | Self::RestOptional) }
+
+    pub fn flag(&self) -> Option<&FlagArg> {
+        match self {
+            Self::Flag(f) | Self::FlagValue(f) => Some(f),
+            _ => None,
+        }
+    }
 }
 
 #[allow(clippy::manual_non_exhaustive)]
@@ -26,91 +36,199 @@ pub struct FieldInfo<'a> {
     pub name: String,
     pub ty: &'a Type,
     pub mode: FieldMode,
+    /// The position of this field in the type's declaration
+    pub index: usize,
     _priv: (),
 }
 
+impl<'a> FieldInfo<'a> {
+    /// Get the type each input token of this field is converted to, if it can
+    /// be determined
+    pub fn value_type(&self) -> Option<&'a Type> {
+        let wrapped = match self.mode {
+            FieldMode::Flag(..) => return None,
+            FieldMode::Required => false,
+            FieldMode::Optional | FieldMode::FlagValue(..) => self.opts.default.is_none(),
+            FieldMode::RestRequired | FieldMode::RestOptional => true,
+        };
+
+        if !wrapped {
+            return Some(self.ty);
+        }
+
+        // Optional arguments are stored in an Option and rest arguments in a
+        // collection, so look for their first type argument
+        let Type::Path(TypePath { ref path, .. }) = self.ty else {
+            return None;
+        };
+        let PathArguments::AngleBracketed(ref args) = path.segments.last()?.arguments else {
+            return None;
+        };
+
+        args.args.iter().find_map(|a| match a {
+            GenericArgument::Type(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// Get the source text of this field's type
+    pub fn type_name(&self) -> String {
+        let s = self.ty.to_token_stream().to_string();
+        let mut ret = String::with_capacity(s.len());
+        let mut chars = s.chars().peekable();
+
+        // Tokens are printed separated by spaces, so remove any next to
+        // punctuation other than after commas, semicolons, and arrows
+        while let Some(c) = chars.next() {
+            if c == ' '
+                && (ret.ends_with(|p| "<:&([".contains(p))
+                    || (ret.ends_with('>') && !ret.ends_with("->"))
+                    || chars.peek().is_some_and(|&n| "<>:,;)]".contains(n)))
+            {
+                continue;
+            }
+
+            ret.push(c);
+        }
+
+        ret
+    }
+}
+
 pub enum FieldInfos<'a> {
     Unit,
     Unnamed(Vec<FieldInfo<'a>>),
     Named(Vec<(Ident, FieldInfo<'a>)>),
 }
 
+/// List the name and mode of each argument in a usage line, in the order
+/// their fields are declared
+fn arg_modes(usage: &CommandUsage) -> impl Iterator<Item = (FieldMode, &str)> {
+    usage
+        .flags
+        .iter()
+        .map(|f| {
+            let name = f.name();
+
+            if f.value.is_some() {
+                (FieldMode::FlagValue(f.clone()), name)
+            } else {
+                (FieldMode::Flag(f.clone()), name)
+            }
+        })
+        .chain(
+            usage
+                .required
+                .iter()
+                .map(|n| (FieldMode::Required, n.as_str())),
+        )
+        .chain(
+            usage
+                .optional
+                .iter()
+                .map(|n| (FieldMode::Optional, n.as_str())),
+        )
+        .chain(match usage.rest {
+            RestArg::None => None,
+            RestArg::Optional(ref n) => Some((FieldMode::RestOptional, n.as_str())),
+            RestArg::Required(ref n) => Some((FieldMode::RestRequired, n.as_str())),
+        })
+}
+
 impl<'a> FieldInfos<'a> {
-    pub fn new(span: Span, usage: &CommandUsage, fields: &'a Fields) -> Result<Self> {
-        let mut args = usage
-            .required
-            .iter()
-            .map(|n| (FieldMode::Required, n))
-            .chain(usage.optional.iter().map(|n| (FieldMode::Optional, n)))
-            .chain(match usage.rest {
-                RestArg::None => None,
-                RestArg::Optional(ref n) => Some((FieldMode::RestOptional, n)),
-                RestArg::Required(ref n) => Some((FieldMode::RestRequired, n)),
-            });
+    pub fn new(span: Span, usage: &CommandUsage, fields: &'a Fields) -> Result<Self, Errors> {
+        let mut args = arg_modes(usage);
 
         let args = match fields {
             Fields::Unit if args.next().is_none() => Ok(FieldInfos::Unit),
-            Fields::Unit => Err((anyhow!("could not locate any fields in unit type"), span)),
+            Fields::Unit => Err((anyhow!("could not locate any fields in unit type"), span).into()),
             Fields::Unnamed(u) => {
                 let mut map: HashMap<_, _> = u.unnamed.iter().enumerate().collect();
 
-                args.enumerate()
-                    .map(|(i, (mode, name))| {
-                        let field = map
-                            .remove(&i)
-                            .ok_or_else(|| (anyhow!("could not locate field {}", i), span))?;
-                        let span = field.span();
+                Errors::collect(
+                    args.enumerate()
+                        .map(|(i, (mode, name))| -> Result<_, Errors> {
+                            let field = map
+                                .remove(&i)
+                                .ok_or_else(|| (anyhow!("could not locate field {}", i), span))?;
+                            let span = field.span();
 
-                        Ok(FieldInfo {
-                            span,
-                            opts: attrs::parse_field(&field.attrs, span)?,
-                            mode,
-                            name: name.into(),
-                            ty: &field.ty,
-                            _priv: (),
-                        })
-                    })
-                    .collect::<Result<_>>()
-                    .map(FieldInfos::Unnamed)
+                            Ok(FieldInfo {
+                                span,
+                                opts: attrs::parse_field(&field.attrs, span)?,
+                                mode,
+                                name: name.into(),
+                                ty: &field.ty,
+                                index: i,
+                                _priv: (),
+                            })
+                        }),
+                )
+                .map(FieldInfos::Unnamed)
             },
             Fields::Named(n) => {
                 let mut map: HashMap<_, _> = n
                     .named
                     .iter()
-                    .map(|f| (f.ident.as_ref().unwrap().to_string(), f))
+                    .enumerate()
+                    .map(|(i, f)| (f.ident.as_ref().unwrap().to_string(), (i, f)))
                     .collect();
 
-                args.map(|(mode, name)| {
-                    let field = map
-                        .remove(name)
-                        .ok_or_else(|| (anyhow!("could not locate field {:?}", name), span))?;
+                Errors::collect(args.map(|(mode, name)| -> Result<_, Errors> {
+                    let ident = name.replace('-', "_");
+                    let (index, field) = map
+                        .remove(&ident)
+                        .ok_or_else(|| (anyhow!("could not locate field {:?}", ident), span))?;
                     let span = field.span();
 
                     Ok((
-                        syn::parse_str(name).map_err(|e| (e.into(), span))?,
+                        syn::parse_str(&ident).map_err(|e| (e.into(), span))?,
                         FieldInfo {
                             span,
                             opts: attrs::parse_field(&field.attrs, span)?,
                             mode,
                             name: name.into(),
                             ty: &field.ty,
+                            index,
                             _priv: (),
                         },
                     ))
-                })
-                .collect::<Result<_>>()
+                }))
                 .map(FieldInfos::Named)
             },
         }?;
 
+        let mut errs = Errors::default();
+
         if args.iter().len() != fields.iter().len() {
-            return Err((anyhow!("mismatched number of fields and arguments"), span));
+            errs.push((anyhow!("mismatched number of fields and arguments"), span));
+        }
+
+        for field in args.iter() {
+            if field.opts.default.is_some()
+                && !matches!(field.mode, FieldMode::Optional | FieldMode::FlagValue(..))
+            {
+                errs.push((
+                    anyhow!(
+                        "default values are only supported for optional arguments and flag \
+                         values"
+                    ),
+                    field.span,
+                ));
+            }
+
+            if field.opts.parse_with.is_some() && matches!(field.mode, FieldMode::Flag(..)) {
+                errs.push((
+                    anyhow!("parse_with cannot be used with flags that take no value"),
+                    field.span,
+                ));
+            }
         }
 
-        Ok(args)
+        errs.finish(args)
     }
 
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         match self {
             Self::Unit => Iter::Unit,
             Self::Unnamed(u) => Iter::Unnamed(u.iter()),
@@ -149,4 +267,4 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
-impl<'a> ExactSizeIterator for Iter<'a> {}
+impl ExactSizeIterator for Iter<'_> {}
@@ -1,13 +1,13 @@
 use syn::{spanned::Spanned, DeriveInput};
 
-use crate::Result;
+use crate::diag::{Diagnostic, Errors};
 
 pub mod command;
 pub mod field;
 
 pub mod prelude {
     pub use proc_macro2::Span;
-    pub use syn::{Generics, Ident, Variant, Visibility};
+    pub use syn::{Generics, Ident, Visibility};
 
     pub use super::{
         command::{Command, CommandVariant, Commands},
@@ -15,8 +15,8 @@ pub mod prelude {
         InputData,
     };
     pub use crate::{
-        docs::{CommandDocs, CommandSetDocs, CommandUsage, RestArg},
-        opts::{CommandOpts, FieldOpts},
+        docs::{CommandDocs, CommandSetDocs, CommandUsage, FlagArg, RestArg},
+        opts::{CommandOpts, CommandSetOpts, FieldDefault, FieldOpts, MatchOpts},
     };
 }
 
@@ -31,15 +31,19 @@ pub struct InputData<'a> {
     pub commands: Commands<'a>,
 }
 
-pub fn assemble(input: &DeriveInput) -> Result<InputData> {
+pub fn assemble(input: &DeriveInput) -> Result<(InputData<'_>, Vec<Diagnostic>), Errors> {
     let commands = Commands::new(input)?;
-
-    Ok(InputData {
-        span: input.span(),
-        vis: &input.vis,
-        ty: &input.ident,
-        generics: &input.generics,
-
-        commands,
-    })
+    let warnings = commands.warnings();
+
+    Ok((
+        InputData {
+            span: input.span(),
+            vis: &input.vis,
+            ty: &input.ident,
+            generics: &input.generics,
+
+            commands,
+        },
+        warnings,
+    ))
 }
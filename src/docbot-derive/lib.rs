@@ -1,18 +1,24 @@
 #![warn(missing_docs, clippy::all, clippy::pedantic, clippy::cargo)]
 #![deny(rustdoc::broken_intra_doc_links, missing_debug_implementations)]
 #![allow(clippy::module_name_repetitions)]
-#![feature(proc_macro_diagnostic)]
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
 
 //! Derive macro for the docbot crate
 
 mod attrs;
 mod bits;
+mod diag;
 mod docs;
 mod inputs;
+mod normalize;
 mod opts;
 mod trie;
 
-use bits::{help::HelpParts, id::IdParts, parse::ParseParts, path::PathParts};
+use bits::{
+    complete::CompleteParts, dispatch::DispatchParts, help::HelpParts, id::IdParts,
+    parse::ParseParts, path::PathParts,
+};
+use diag::Diagnostic;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote_spanned;
@@ -26,20 +32,17 @@ pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 pub fn derive_docbot(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input);
 
-    let inputs = match inputs::assemble(&input) {
+    let (inputs, warnings) = match inputs::assemble(&input) {
         Ok(s) => s,
-        Err((e, s)) => {
-            s.unwrap()
-                .error(format!("Macro execution failed:\n{:?}", e))
-                .emit();
-            return TokenStream::new();
-        },
+        Err(e) => return e.emit().into(),
     };
 
     let id_parts = bits::id::emit(&inputs);
     let path_parts = bits::path::emit(&inputs, &id_parts);
     let parse_parts = bits::parse::emit(&inputs, &id_parts, &path_parts);
     let help_parts = bits::help::emit(&inputs, &path_parts);
+    let complete_parts = bits::complete::emit(&inputs, &id_parts);
+    let dispatch_parts = bits::dispatch::emit(&inputs);
 
     // Quote variables
     let IdParts {
@@ -50,12 +53,23 @@ pub fn derive_docbot(input: TokenStream) -> TokenStream {
     } = path_parts;
     let ParseParts { items: parse_items } = parse_parts;
     let HelpParts { items: help_items } = help_parts;
+    let CompleteParts {
+        items: complete_items,
+    } = complete_parts;
+    let DispatchParts {
+        items: dispatch_items,
+    } = dispatch_parts;
+
+    let warnings = warnings.into_iter().map(Diagnostic::emit);
 
     let toks = quote_spanned! { input.span() =>
+        #(#warnings)*
         #id_items
         #path_items
         #parse_items
         #help_items
+        #complete_items
+        #dispatch_items
     };
 
     // eprintln!("{}", toks);
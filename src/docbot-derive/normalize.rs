@@ -0,0 +1,74 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote_spanned, ToTokens};
+
+/// Unicode normalization applied to command IDs and user input, mirroring
+/// `docbot::Normalization`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Normalization {
+    pub unicode: bool,
+    pub strip_diacritics: bool,
+}
+
+impl Normalization {
+    fn is_enabled(self) -> bool { self.unicode || self.strip_diacritics }
+
+    /// Normalize a command ID, matching what the emitted code does to user
+    /// input at runtime
+    #[cfg(feature = "unicode")]
+    pub fn apply(self, s: &str, fold_case: bool) -> String {
+        use caseless::Caseless;
+        use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+        if !self.is_enabled() {
+            return if fold_case {
+                s.to_lowercase()
+            } else {
+                s.to_owned()
+            };
+        }
+
+        let chars = s
+            .nfkd()
+            .filter(|&c| !(self.strip_diacritics && is_combining_mark(c)));
+
+        if fold_case {
+            chars.default_case_fold().nfkc().collect()
+        } else {
+            chars.nfkc().collect()
+        }
+    }
+
+    /// Normalize a command ID, matching what the emitted code does to user
+    /// input at runtime
+    #[cfg(not(feature = "unicode"))]
+    pub fn apply(self, s: &str, fold_case: bool) -> String {
+        // Attribute parsing rejects normalization options without the feature
+        debug_assert!(!self.is_enabled());
+
+        if fold_case {
+            s.to_lowercase()
+        } else {
+            s.to_owned()
+        }
+    }
+
+    /// Emit an expression normalizing the string `s` at runtime
+    pub fn emit(self, span: Span, s: impl ToTokens, fold_case: bool) -> TokenStream {
+        let Self {
+            unicode,
+            strip_diacritics,
+        } = self;
+
+        match (self.is_enabled(), fold_case) {
+            (false, false) => quote_spanned! { span => #s },
+            (false, true) => quote_spanned! { span => #s.to_lowercase() },
+            (true, _) => quote_spanned! { span =>
+                ::docbot::Normalization {
+                    unicode: #unicode,
+                    strip_diacritics: #strip_diacritics,
+                }
+                .apply(#s, #fold_case)
+            },
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::anyhow;
-use syn::{spanned::Spanned, Attribute, Meta, NestedMeta};
+use proc_macro2::Span;
+use syn::{
+    meta::ParseNestedMeta, spanned::Spanned, Attribute, LitBool, LitInt, LitStr, Path, Token,
+};
 
-use crate::Result;
+use crate::{normalize::Normalization, Result};
 
 pub trait ParseOpts: Sized {
     fn parse_opts(attr: &Attribute) -> Result<Self>;
@@ -9,43 +12,295 @@ pub trait ParseOpts: Sized {
     fn no_opts() -> Result<Self, anyhow::Error>;
 }
 
+/// Parse each item of a `#[docbot(...)]` attribute with `f`, which should
+/// return `false` if the item was not recognized
+fn parse_items(
+    attr: &Attribute,
+    kind: &str,
+    mut f: impl FnMut(&ParseNestedMeta) -> syn::Result<bool>,
+) -> Result<()> {
+    attr.parse_nested_meta(|meta| {
+        if f(&meta)? {
+            Ok(())
+        } else {
+            Err(meta.error(format!("unexpected value in #[docbot] {kind} attribute")))
+        }
+    })
+    .map_err(|e| {
+        let span = e.span();
+        (e.into(), span)
+    })
+}
+
+/// Returns true if the current item is a bare path with no value
+fn is_flag(meta: &ParseNestedMeta, name: &str) -> bool {
+    meta.path.is_ident(name) && !meta.input.peek(Token![=])
+}
+
+/// Parse the value of a `name = "..."` item
+fn lit_str(meta: &ParseNestedMeta, what: &str) -> syn::Result<LitStr> {
+    let value = meta.value()?;
+
+    value
+        .parse()
+        .map_err(|e| syn::Error::new(e.span(), format!("expected a string literal for {what}")))
+}
+
+/// Parse a switch given either as a bare `name` or as `name = true|false`
+fn switch(meta: &ParseNestedMeta, name: &str, slot: &mut Option<bool>) -> syn::Result<bool> {
+    if !meta.path.is_ident(name) {
+        return Ok(false);
+    }
+
+    if slot.is_some() {
+        return Err(meta.error(format!("duplicate {name} specifier")));
+    }
+
+    *slot = Some(if meta.input.peek(Token![=]) {
+        let lit: LitBool = meta.value()?.parse().map_err(|e| {
+            syn::Error::new(e.span(), format!("expected a boolean literal for {name}"))
+        })?;
+
+        lit.value
+    } else {
+        true
+    });
+
+    Ok(true)
+}
+
+/// Fail if Unicode normalization was requested without the `unicode` feature
+fn require_unicode(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if cfg!(feature = "unicode") {
+        Ok(())
+    } else {
+        Err(meta.error("Unicode normalization requires the unicode feature of docbot"))
+    }
+}
+
+/// Options controlling how input is matched against command IDs
+///
+/// Options left unset on a command are inherited from its command set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MatchOpts {
+    /// Only accept complete IDs, rather than any unambiguous prefix
+    pub exact: Option<bool>,
+    /// The minimum number of characters required to match an ID by prefix
+    pub min_prefix: Option<usize>,
+    /// Match IDs exactly as written instead of folding case
+    pub case_sensitive: Option<bool>,
+    /// Silence warnings about IDs that are strict prefixes of other IDs
+    pub allow_prefix: Option<bool>,
+}
+
+impl MatchOpts {
+    /// Try to consume an item of a `#[docbot]` attribute, returning `false` if
+    /// it was not recognized
+    fn parse_item(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if switch(meta, "exact", &mut self.exact)?
+            || switch(meta, "case_sensitive", &mut self.case_sensitive)?
+            || switch(meta, "allow_prefix", &mut self.allow_prefix)?
+        {
+            return Ok(true);
+        }
+
+        if meta.path.is_ident("min_prefix") {
+            if self.min_prefix.is_some() {
+                return Err(meta.error("duplicate min_prefix specifier"));
+            }
+
+            let lit: LitInt = meta.value()?.parse().map_err(|e| {
+                syn::Error::new(
+                    e.span(),
+                    "expected an integer literal for minimum prefix length",
+                )
+            })?;
+
+            self.min_prefix = Some(lit.base10_parse()?);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Combine command-level options with the options of the containing
+    /// command set, preferring the former
+    pub fn or(self, set: Self) -> Self {
+        Self {
+            exact: self.exact.or(set.exact),
+            min_prefix: self.min_prefix.or(set.min_prefix),
+            case_sensitive: self.case_sensitive.or(set.case_sensitive),
+            allow_prefix: self.allow_prefix.or(set.allow_prefix),
+        }
+    }
+
+    pub fn is_exact(&self) -> bool { self.exact.unwrap_or(false) }
+
+    pub fn is_case_sensitive(&self) -> bool { self.case_sensitive.unwrap_or(false) }
+
+    pub fn allows_prefix(&self) -> bool { self.allow_prefix.unwrap_or(false) }
+
+    /// Get the length of the shortest prefix of `id` accepted by the lexer
+    pub fn min_len(&self, id: &str) -> usize {
+        let len = id.chars().count();
+
+        if self.is_exact() {
+            len
+        } else {
+            self.min_prefix.map_or(0, |m| m.min(len))
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CommandSetOpts {
+    pub dispatch: bool,
+    pub async_dispatch: bool,
+    pub send_dispatch: bool,
+    pub normalize: Normalization,
+    pub matching: MatchOpts,
+}
+
+impl CommandSetOpts {
+    /// Try to consume an item of a container-level `#[docbot]` attribute,
+    /// returning `false` if it was not recognized
+    fn parse_item(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if is_flag(meta, "dispatch") {
+            if self.dispatch {
+                return Err(meta.error("duplicate dispatch specifier"));
+            }
+
+            self.dispatch = true;
+        } else if meta.path.is_ident("async_dispatch") {
+            if self.async_dispatch {
+                return Err(meta.error("duplicate async_dispatch specifier"));
+            }
+
+            self.async_dispatch = true;
+
+            if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|meta| {
+                    if is_flag(&meta, "send") && !self.send_dispatch {
+                        self.send_dispatch = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected a single `send` in async_dispatch(...)"))
+                    }
+                })?;
+            }
+        } else if is_flag(meta, "normalize") {
+            if self.normalize.unicode {
+                return Err(meta.error("duplicate normalize specifier"));
+            }
+
+            require_unicode(meta)?;
+            self.normalize.unicode = true;
+        } else if is_flag(meta, "strip_diacritics") {
+            if self.normalize.strip_diacritics {
+                return Err(meta.error("duplicate strip_diacritics specifier"));
+            }
+
+            require_unicode(meta)?;
+            self.normalize.strip_diacritics = true;
+        } else {
+            return self.matching.parse_item(meta);
+        }
+
+        Ok(true)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.dispatch
+            || self.async_dispatch
+            || self.normalize.unicode
+            || self.normalize.strip_diacritics)
+    }
+}
+
+impl ParseOpts for CommandSetOpts {
+    fn parse_opts(attr: &Attribute) -> Result<Self> {
+        let mut ret = Self::default();
+
+        parse_items(attr, "command set", |meta| ret.parse_item(meta))?;
+
+        Ok(ret)
+    }
+
+    fn no_opts() -> Result<Self, anyhow::Error> { Ok(Self::default()) }
+}
+
+/// An additional command ID accepted only when parsing in a given locale
+#[derive(Debug)]
+pub struct LocaleAlias {
+    pub locale: String,
+    pub name: String,
+    pub span: Span,
+}
+
 #[derive(Debug, Default)]
 pub struct CommandOpts {
     pub subcommand: bool,
+    pub hidden: bool,
+    pub deprecated: Option<String>,
+    pub requires: Vec<String>,
+    pub aliases: Vec<LocaleAlias>,
+    pub matching: MatchOpts,
+    /// Container-level options, only valid when deriving on a struct
+    pub set: CommandSetOpts,
+}
+
+impl CommandOpts {
+    fn parse_item(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if is_flag(meta, "subcommand") {
+            if self.subcommand {
+                return Err(meta.error("duplicate subcommand specifier"));
+            }
+
+            self.subcommand = true;
+        } else if is_flag(meta, "hidden") {
+            if self.hidden {
+                return Err(meta.error("duplicate hidden specifier"));
+            }
+
+            self.hidden = true;
+        } else if meta.path.is_ident("deprecated") {
+            if self.deprecated.is_some() {
+                return Err(meta.error("duplicate deprecated specifier"));
+            }
+
+            self.deprecated = Some(lit_str(meta, "deprecation notice")?.value());
+        } else if meta.path.is_ident("requires") {
+            self.requires
+                .push(lit_str(meta, "required permission")?.value());
+        } else if meta.path.is_ident("alias") {
+            meta.parse_nested_meta(|meta| {
+                // Locales such as pt-BR are written as pt_BR, since hyphens
+                // aren't allowed in identifiers
+                let locale = meta.path.require_ident()?.to_string().replace('_', "-");
+                let name = lit_str(&meta, "localized alias")?;
+
+                self.aliases.push(LocaleAlias {
+                    locale,
+                    name: name.value(),
+                    span: name.span(),
+                });
+
+                Ok(())
+            })?;
+        } else if !self.matching.parse_item(meta)? {
+            return self.set.parse_item(meta);
+        }
+
+        Ok(true)
+    }
 }
 
 impl ParseOpts for CommandOpts {
     fn parse_opts(attr: &Attribute) -> Result<Self> {
-        let meta = attr.parse_meta().map_err(|e| (e.into(), attr.span()))?;
         let mut ret = Self::default();
 
-        match meta {
-            Meta::List(l) => {
-                for item in l.nested {
-                    match item {
-                        NestedMeta::Meta(Meta::Path(p)) if p.is_ident("subcommand") => {
-                            if ret.subcommand {
-                                return Err((anyhow!("duplicate subcommand specifier"), p.span()));
-                            }
-
-                            ret.subcommand = true;
-                        },
-                        i => {
-                            return Err((
-                                anyhow!("unexpected value in #[docbot] command attribute"),
-                                i.span(),
-                            ));
-                        },
-                    }
-                }
-            },
-            _ => {
-                return Err((
-                    anyhow!("invalid #[docbot] attribute format, expected #[docbot(...)]",),
-                    attr.span(),
-                ));
-            },
-        }
+        parse_items(attr, "command", |meta| ret.parse_item(meta))?;
 
         Ok(ret)
     }
@@ -53,42 +308,64 @@ impl ParseOpts for CommandOpts {
     fn no_opts() -> Result<Self, anyhow::Error> { Ok(Self::default()) }
 }
 
+#[derive(Debug)]
+pub enum FieldDefault {
+    /// Use the field type's [`Default`] implementation
+    Trait,
+    /// Convert the given string as if it were the argument's input
+    Value(LitStr),
+}
+
 #[derive(Debug, Default)]
 pub struct FieldOpts {
     pub path: bool,
+    pub default: Option<FieldDefault>,
+    pub parse_with: Option<Path>,
+}
+
+impl FieldOpts {
+    fn parse_item(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if is_flag(meta, "path") {
+            if self.path {
+                return Err(meta.error("duplicate path specifier"));
+            }
+
+            self.path = true;
+        } else if meta.path.is_ident("default") {
+            if self.default.is_some() {
+                return Err(meta.error("duplicate default specifier"));
+            }
+
+            self.default = Some(if meta.input.peek(Token![=]) {
+                FieldDefault::Value(lit_str(meta, "default value")?)
+            } else {
+                FieldDefault::Trait
+            });
+        } else if meta.path.is_ident("parse_with") {
+            if self.parse_with.is_some() {
+                return Err(meta.error("duplicate parse_with specifier"));
+            }
+
+            self.parse_with = Some(meta.value()?.parse()?);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
 }
 
 impl ParseOpts for FieldOpts {
     fn parse_opts(attr: &Attribute) -> Result<Self> {
-        let meta = attr.parse_meta().map_err(|e| (e.into(), attr.span()))?;
         let mut ret = Self::default();
 
-        match meta {
-            Meta::List(l) => {
-                for item in l.nested {
-                    match item {
-                        NestedMeta::Meta(Meta::Path(p)) if p.is_ident("path") => {
-                            if ret.path {
-                                return Err((anyhow!("duplicate path specifier"), p.span()));
-                            }
-
-                            ret.path = true;
-                        },
-                        i => {
-                            return Err((
-                                anyhow!("unexpected value in #[docbot] field attribute"),
-                                i.span(),
-                            ));
-                        },
-                    }
-                }
-            },
-            _ => {
-                return Err((
-                    anyhow!("invalid #[docbot] attribute format, expected #[docbot(...)]",),
-                    attr.span(),
-                ));
-            },
+        parse_items(attr, "field", |meta| ret.parse_item(meta))?;
+
+        if ret.path && ret.parse_with.is_some() {
+            return Err((
+                anyhow!("path and parse_with cannot be used together"),
+                attr.span(),
+            ));
         }
 
         Ok(ret)
@@ -1,19 +1,41 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
 
-use anyhow::anyhow;
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{quote_spanned, ToTokens};
 
-use crate::Result;
-
 pub struct Trie<T> {
     payloads: Box<[(String, T)]>,
+    min_lens: Box<[usize]>,
+    prefixes: Box<[(usize, usize)]>,
     root: TrieNode,
 }
 
-pub struct TrieNodeRef<'a, T>(&'a Trie<T>, &'a TrieNode);
+/// Error returned when two trie entries share the same key
+#[derive(Debug)]
+pub struct DuplicateKey {
+    pub key: String,
+    /// The index of the entry first inserted with this key
+    pub first: usize,
+    /// The index of the entry that collided with the first
+    pub second: usize,
+}
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "multiple entries for identifier {:?}", self.key)
+    }
+}
+
+impl std::error::Error for DuplicateKey {}
+
+pub struct TrieNodeRef<'a, T>(&'a Trie<T>, &'a TrieNode, usize);
 
 struct TrieNode {
+    /// The entry whose key ends at this node, if any
+    terminal: Option<usize>,
     payloads: Box<[usize]>,
     children: HashMap<char, TrieNode>,
 }
@@ -22,22 +44,28 @@ struct TrieNode {
 struct TrieNodeParts(Option<usize>, HashMap<char, TrieNodeParts>);
 
 impl<T> Trie<T> {
-    pub fn new<I: IntoIterator<Item = (S, T)>, S: AsRef<str>>(
+    /// Construct a new trie from a list of keys, payloads, and the length of
+    /// the shortest prefix of each key that should be accepted by the lexer
+    pub fn new<I: IntoIterator<Item = (S, T, usize)>, S: AsRef<str>>(
         it: I,
-    ) -> Result<Self, anyhow::Error> {
+    ) -> Result<Self, DuplicateKey> {
         fn insert_payload<I: Iterator<Item = char>>(
             parts: &mut TrieNodeParts,
             payload: usize,
             mut path: I,
             mut breadcrumb: String,
-        ) -> Result<(), anyhow::Error> {
+        ) -> Result<(), DuplicateKey> {
             match path.next() {
                 None => match parts.0 {
                     None => {
                         parts.0 = Some(payload);
                         Ok(())
                     },
-                    Some(_) => Err(anyhow!("multiple entries for identifier {:?}", breadcrumb)),
+                    Some(first) => Err(DuplicateKey {
+                        key: breadcrumb,
+                        first,
+                        second: payload,
+                    }),
                 },
                 Some(c) => {
                     use std::collections::hash_map::Entry::{Occupied, Vacant};
@@ -54,39 +82,111 @@ impl<T> Trie<T> {
         }
 
         let mut payloads = Vec::new();
+        let mut min_lens = Vec::new();
         let mut root = TrieNodeParts::default();
 
-        for (path, payload) in it {
+        for (path, payload, min_len) in it {
             let id = payloads.len();
             payloads.push((path.as_ref().into(), payload));
+            min_lens.push(min_len);
 
             insert_payload(&mut root, id, path.as_ref().chars(), String::new())?;
         }
 
+        let mut prefixes = Vec::new();
+        let root = TrieNode::new(root, &mut prefixes);
+
         Ok(Self {
             payloads: payloads.into_boxed_slice(),
-            root: root.into(),
+            min_lens: min_lens.into_boxed_slice(),
+            prefixes: prefixes.into_boxed_slice(),
+            root,
         })
     }
 
-    pub fn root(&self) -> TrieNodeRef<T> { TrieNodeRef(self, &self.root) }
+    pub fn payload(&self, index: usize) -> &(String, T) { &self.payloads[index] }
+
+    /// List all pairs of entries `(a, b)` where the key of `a` is a strict
+    /// prefix of the key of `b`, shadowing `b` for inputs shorter than `a`
+    pub fn strict_prefixes(&self) -> &[(usize, usize)] { &self.prefixes }
+
+    pub fn root(&self) -> TrieNodeRef<'_, T> { TrieNodeRef(self, &self.root, 0) }
 }
 
 impl<'a, T> TrieNodeRef<'a, T> {
-    pub fn payloads(&self) -> impl Iterator<Item = &(String, T)> {
-        self.1.payloads.iter().map(move |i| &self.0.payloads[*i])
+    /// List the indices of the payloads the lexer accepts at this node
+    ///
+    /// An entry whose key ends at or below this node shadows any longer keys
+    /// beneath it, unless the input is too short to select that entry, in
+    /// which case the longer keys are considered instead.
+    fn accepted(&self) -> Vec<usize> {
+        fn visit(trie: &Trie<impl Sized>, node: &TrieNode, len: usize, out: &mut Vec<usize>) {
+            match node.terminal {
+                Some(i) if trie.min_lens[i] <= len => out.push(i),
+                _ => {
+                    for child in node.children.values() {
+                        visit(trie, child, len, out);
+                    }
+                },
+            }
+        }
+
+        let mut accepted = vec![];
+        visit(self.0, self.1, self.2, &mut accepted);
+        accepted.sort_unstable();
+        accepted
     }
 
     pub fn children(&'a self) -> impl ExactSizeIterator<Item = (char, TrieNodeRef<'a, T>)> {
         self.1
             .children
             .iter()
-            .map(move |(k, v)| (*k, TrieNodeRef(self.0, v)))
+            .map(move |(k, v)| (*k, TrieNodeRef(self.0, v, self.2 + 1)))
+    }
+
+    fn subtree_payloads(&self, set: &mut BTreeSet<usize>) {
+        set.extend(self.1.payloads.iter().copied());
+
+        for (_, child) in self.children() {
+            child.subtree_payloads(set);
+        }
+    }
+
+    pub fn to_completer<I: ToTokens + Clone, N: Clone + Fn(usize) -> Option<Literal>>(
+        &self,
+        span: Span,
+        iter_id: I,
+        name: N,
+    ) -> TokenStream {
+        let arms = self.children().map(|(chr, child)| {
+            let chr = Literal::character(chr);
+            let child = child.to_completer(span, iter_id.clone(), name.clone());
+
+            quote_spanned! { span => #chr => #child }
+        });
+
+        let mut set = BTreeSet::new();
+        self.subtree_payloads(&mut set);
+        let names = set.into_iter().filter_map(name.clone());
+
+        quote_spanned! { span =>
+            match #iter_id.next() {
+                None => &[#(#names),*],
+                Some(c) => match c {
+                    #(#arms,)*
+                    _ => &[],
+                },
+            }
+        }
     }
 
+    /// Emit a lexer matching the characters of `iter_id` against this node
+    ///
+    /// On a match, `ok` is called with the matched payload, the indices of all
+    /// entries resolving to it, and the number of characters consumed.
     pub fn to_lexer<
         I: ToTokens + Clone,
-        O: Clone + Fn(&T) -> OR,
+        O: Clone + Fn(&T, Vec<usize>, usize) -> OR,
         OR: ToTokens,
         N: Clone + Fn() -> NR,
         NR: ToTokens,
@@ -116,20 +216,18 @@ impl<'a, T> TrieNodeRef<'a, T> {
             quote_spanned! { span => #chr => #child }
         });
 
-        let mut payloads = self.payloads();
-        let eof: Box<dyn ToTokens> = match payloads.next() {
-            None => Box::new(no_match()),
-            Some((_, p)) => match payloads.next() {
-                None => Box::new(ok(p)),
-                Some(_) => {
-                    let payloads = self.payloads().map(|(s, _)| &**s).collect::<Vec<_>>();
-
-                    resolve_ambiguous(self.payloads().collect())
-                        .map_or_else::<Box<dyn ToTokens>, _, _>(
-                            || Box::new(ambiguous(payloads)),
-                            |r| Box::new(ok(r)),
-                        )
-                },
+        let accepted = self.accepted();
+        let eof: Box<dyn ToTokens> = match *accepted {
+            [] => Box::new(no_match()),
+            [i] => Box::new(ok(&self.0.payloads[i].1, accepted, self.2)),
+            _ => {
+                let payloads = accepted.iter().map(|i| &self.0.payloads[*i]);
+
+                resolve_ambiguous(payloads.clone().collect())
+                    .map_or_else::<Box<dyn ToTokens>, _, _>(
+                        || Box::new(ambiguous(payloads.map(|(s, _)| &**s).collect())),
+                        |r| Box::new(ok(r, accepted.clone(), self.2)),
+                    )
             },
         };
 
@@ -155,24 +253,31 @@ impl<'a, T> TrieNodeRef<'a, T> {
     }
 }
 
-impl From<TrieNodeParts> for TrieNode {
-    fn from(TrieNodeParts(payload, children): TrieNodeParts) -> Self {
-        let children: HashMap<_, TrieNode> =
-            children.into_iter().map(|(k, v)| (k, v.into())).collect();
-
-        // TODO: warn when a strict prefix occurs?
-        let payloads = payload.map_or_else(
-            || {
-                children
-                    .values()
-                    .flat_map(|v| v.payloads.iter())
-                    .copied()
-                    .collect::<Vec<_>>()
-                    .into_boxed_slice()
+impl TrieNode {
+    fn new(
+        TrieNodeParts(payload, children): TrieNodeParts,
+        prefixes: &mut Vec<(usize, usize)>,
+    ) -> Self {
+        let children: HashMap<_, TrieNode> = children
+            .into_iter()
+            .map(|(k, v)| (k, TrieNode::new(v, prefixes)))
+            .collect();
+
+        let subtree = || children.values().flat_map(|v| v.payloads.iter()).copied();
+
+        let payloads = match payload {
+            Some(p) => {
+                prefixes.extend(subtree().map(|c| (p, c)));
+
+                Box::new([p]) as Box<[_]>
             },
-            |p| Box::new([p]),
-        );
+            None => subtree().collect::<Vec<_>>().into_boxed_slice(),
+        };
 
-        Self { payloads, children }
+        Self {
+            terminal: payload,
+            payloads,
+            children,
+        }
     }
 }
@@ -0,0 +1,136 @@
+use super::{ArgumentUsage, Command, CommandId, CommandUsage, FlagUsage};
+
+/// A candidate value for the token under the cursor
+#[derive(Debug, Clone)]
+pub enum Completion {
+    /// A command or subcommand ID beginning with the partial token
+    Id(&'static str),
+    /// A flag beginning with the partial token, including its leading dashes
+    Flag(String),
+    /// A placeholder for the argument expected at the cursor
+    Argument(&'static ArgumentUsage),
+}
+
+/// A command supporting completion of partially-typed input
+pub trait Complete: Command {
+    /// List completions for a partial list of arguments following the command
+    /// ID `id`, where the last argument is the token under the cursor
+    fn complete_args(id: Self::Id, args: &[&str]) -> Vec<Completion>;
+
+    /// List completions for a partial command, where the last token is the
+    /// token under the cursor
+    ///
+    /// To complete a new token (e.g. when the cursor follows whitespace), the
+    /// last token should be empty.
+    fn complete<I: IntoIterator<Item = S>, S: AsRef<str>>(iter: I) -> Vec<Completion> {
+        let toks: Vec<_> = iter.into_iter().collect();
+        let toks: Vec<_> = toks.iter().map(AsRef::as_ref).collect();
+
+        match toks.split_first() {
+            None => Self::Id::complete("")
+                .iter()
+                .copied()
+                .map(Completion::Id)
+                .collect(),
+            Some((head, [])) => Self::Id::complete(head)
+                .iter()
+                .copied()
+                .map(Completion::Id)
+                .collect(),
+            Some((head, args)) => head
+                .parse()
+                .map_or_else(|_| vec![], |id| Self::complete_args(id, args)),
+        }
+    }
+}
+
+impl CommandUsage {
+    fn find_flag(&'static self, tok: &str) -> Option<&'static ArgumentUsage> {
+        let (long, name) = match tok.strip_prefix("--") {
+            Some(l) => (true, l),
+            None => (false, tok.strip_prefix('-')?),
+        };
+
+        self.args.iter().find(|a| match a.flag {
+            Some(FlagUsage { short, long: l, .. }) => {
+                if long {
+                    l == Some(name)
+                } else {
+                    short == Some(name)
+                }
+            },
+            None => false,
+        })
+    }
+
+    /// List completions for a partial list of arguments to this command, where
+    /// the last argument is the token under the cursor
+    #[must_use]
+    pub fn complete_args(&'static self, args: &[&str]) -> Vec<Completion> {
+        let (partial, done) = args.split_last().map_or(("", &[][..]), |(p, d)| (*p, d));
+        let positional: Vec<_> = self.args.iter().filter(|a| a.flag.is_none()).collect();
+        let mut pos = 0;
+        let mut value_for = None;
+        let mut flags_done = false;
+
+        for tok in done {
+            if value_for.take().is_some() {
+                continue;
+            }
+
+            if !flags_done {
+                if *tok == "--" {
+                    flags_done = true;
+                    continue;
+                }
+
+                if let Some(flag) = self.find_flag(tok) {
+                    if flag.flag.as_ref().is_some_and(|f| f.value.is_some()) {
+                        value_for = Some(flag);
+                    }
+
+                    continue;
+                }
+            }
+
+            // As when parsing, flags after the start of a command path belong
+            // to the command it names
+            if positional.get(pos).is_some_and(|a| a.is_path) {
+                flags_done = true;
+            }
+
+            pos += 1;
+        }
+
+        if let Some(flag) = value_for {
+            return vec![Completion::Argument(flag)];
+        }
+
+        let mut ret = vec![];
+
+        if !flags_done && partial.starts_with('-') {
+            for flag in self.args.iter().filter_map(|a| a.flag.as_ref()) {
+                let names = flag
+                    .short
+                    .map(|s| format!("-{s}"))
+                    .into_iter()
+                    .chain(flag.long.map(|l| format!("--{l}")));
+
+                ret.extend(
+                    names
+                        .filter(|n| n.starts_with(partial))
+                        .map(Completion::Flag),
+                );
+            }
+        }
+
+        if let Some(arg) = positional
+            .get(pos)
+            .or_else(|| positional.iter().find(|a| a.is_rest))
+        {
+            ret.push(Completion::Argument(arg));
+        }
+
+        ret
+    }
+}
@@ -0,0 +1,51 @@
+use std::future::Future;
+
+/// A command which can be routed to a method on a handler of type `H`
+///
+/// This trait is implemented by the derive macro when the command type is
+/// annotated with `#[docbot(dispatch)]`.  The macro also generates a handler
+/// trait named after the command type (e.g. `MyCommandHandler<Ctx>`) with one
+/// method per command, taking the context followed by the command's arguments
+/// in the order its fields are declared.  Parameters for tuple fields are named
+/// after their arguments in `snake_case`, or `arg0`, `arg1`, etc. if an
+/// argument's name is not a valid identifier.  Subcommands are dispatched
+/// recursively, so a handler for a command set with subcommands must implement
+/// the handler traits for those as well.
+pub trait Dispatch<H: ?Sized, Ctx> {
+    /// The value returned by the handler
+    type Output;
+
+    /// Pass this command and its arguments to the corresponding method of
+    /// `handler`
+    fn dispatch(self, handler: &mut H, ctx: Ctx) -> Self::Output;
+}
+
+/// An asynchronous version of [`Dispatch`]
+///
+/// This trait is implemented by the derive macro when the command type is
+/// annotated with `#[docbot(async_dispatch)]`.  The generated handler trait is
+/// named e.g. `MyCommandAsyncHandler<Ctx>`, and its methods return futures.
+///
+/// The future returned by [`dispatch_async`](Self::dispatch_async) is not
+/// known to be [`Send`]; see [`SendAsyncDispatch`] for use with multithreaded
+/// executors.
+pub trait AsyncDispatch<H: ?Sized, Ctx> {
+    /// The value produced by the handler
+    type Output;
+
+    /// Pass this command and its arguments to the corresponding method of
+    /// `handler`, returning a future resolving to the handler's output
+    fn dispatch_async(self, handler: &mut H, ctx: Ctx) -> impl Future<Output = Self::Output>;
+}
+
+/// A version of [`AsyncDispatch`] whose futures are [`Send`]
+///
+/// This trait is implemented alongside [`AsyncDispatch`] when the command type
+/// is annotated with `#[docbot(async_dispatch(send))]`, in which case the
+/// methods of the generated handler trait must return futures that are `Send`.
+/// Subcommands must be annotated the same way.
+pub trait SendAsyncDispatch<H: ?Sized, Ctx>: AsyncDispatch<H, Ctx> {
+    /// Pass this command and its arguments to the corresponding method of
+    /// `handler`, returning a future resolving to the handler's output
+    fn dispatch_send(self, handler: &mut H, ctx: Ctx) -> impl Future<Output = Self::Output> + Send;
+}
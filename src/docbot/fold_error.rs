@@ -1,6 +1,10 @@
 use std::{fmt, fmt::Write};
 
-use super::{Anyhow, ArgumentName, CommandParseError, IdParseError, PathParseError};
+use super::{
+    Anyhow, ArgumentName, ArgumentValue, CommandParseError, CommandUsage, DefaultSuggester,
+    FlatParseError, FoldHelp, IdParseError, PathParseError, SimpleFoldHelp, Span, Suggester,
+    TokenizeError,
+};
 
 /// Helper for downcasting [`anyhow::Error`] into possible `docbot` errors
 #[derive(Debug)]
@@ -11,6 +15,8 @@ pub enum Downcast {
     IdParse(IdParseError),
     /// The error contained a [`PathParseError`]
     PathParse(PathParseError),
+    /// The error contained a [`TokenizeError`]
+    Tokenize(TokenizeError),
     /// The error was unable to be downcast
     Other(Anyhow),
 }
@@ -32,6 +38,7 @@ impl From<Anyhow> for Downcast {
             Self::CommandParse,
             Self::IdParse,
             Self::PathParse,
+            Self::Tokenize,
             Self::Other,
         )
     }
@@ -48,10 +55,19 @@ pub trait FoldError {
             Downcast::CommandParse(c) => self.fold_command_parse(c),
             Downcast::IdParse(i) => self.fold_id_parse(i),
             Downcast::PathParse(p) => self.fold_path_parse(p),
+            Downcast::Tokenize(t) => self.fold_tokenize(t),
             Downcast::Other(o) => self.other(o),
         }
     }
 
+    /// Handle a [`TokenizeError`]
+    fn fold_tokenize(&self, err: TokenizeError) -> Self::Output {
+        match err {
+            TokenizeError::UnterminatedQuote(quote, start) => self.unterminated_quote(quote, start),
+            TokenizeError::DanglingEscape(pos) => self.dangling_escape(pos),
+        }
+    }
+
     /// Handle an [`IdParseError`]
     fn fold_id_parse(&self, err: IdParseError) -> Self::Output {
         match err {
@@ -77,16 +93,63 @@ pub trait FoldError {
             CommandParseError::MissingRequired(ArgumentName { cmd, arg }) => {
                 self.missing_required(cmd, arg)
             },
-            CommandParseError::BadConvert(ArgumentName { cmd, arg }, err) => {
-                self.bad_convert(cmd, arg, self.fold_anyhow(err))
+            CommandParseError::MissingFlagValue(ArgumentName { cmd, arg }) => {
+                self.missing_flag_value(cmd, arg)
+            },
+            CommandParseError::BadConvert(ArgumentName { cmd, arg }, err, value) => {
+                // Errors from parsing command IDs already include suggestions
+                let is_id = err.is::<IdParseError>() || err.is::<PathParseError>();
+                let inner = self.fold_anyhow(err);
+
+                match value {
+                    Some(value) if !is_id => self.bad_value(cmd, arg, inner, value),
+                    _ => self.bad_convert(cmd, arg, inner),
+                }
             },
             CommandParseError::Trailing(cmd, extra) => self.trailing(cmd, extra),
             CommandParseError::Subcommand(subcmd, err) => {
                 self.subcommand(subcmd, self.fold_command_parse(*err))
             },
+            CommandParseError::Spanned(span, err) => {
+                self.spanned(span, self.fold_command_parse(*err))
+            },
         }
     }
 
+    /// Handle a [`CommandParseError`] flattened with
+    /// [`CommandParseError::flatten`], passing the full path of the command
+    /// that failed to [`command_path`](Self::command_path) and its usage, if
+    /// known, to [`usage`](Self::usage)
+    fn fold_flat_parse(&self, err: FlatParseError) -> Self::Output {
+        let FlatParseError { path, error, usage } = err;
+        let inner = self.fold_command_parse(error);
+
+        let inner = match usage {
+            Some(usage) => {
+                let parents = path.split_last().map_or(&[][..], |(_, p)| p);
+
+                self.usage(parents, usage, inner)
+            },
+            None => inner,
+        };
+
+        self.command_path(&path, inner)
+    }
+
+    /// Handle a value of [`TokenizeError::UnterminatedQuote`]
+    ///
+    /// By default the error is passed to [`other`](Self::other).
+    fn unterminated_quote(&self, quote: char, start: usize) -> Self::Output {
+        self.other(TokenizeError::UnterminatedQuote(quote, start).into())
+    }
+
+    /// Handle a value of [`TokenizeError::DanglingEscape`]
+    ///
+    /// By default the error is passed to [`other`](Self::other).
+    fn dangling_escape(&self, pos: usize) -> Self::Output {
+        self.other(TokenizeError::DanglingEscape(pos).into())
+    }
+
     /// Handle a value of [`IdParseError::NoMatch`]
     fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output;
 
@@ -111,6 +174,13 @@ pub trait FoldError {
     /// Handle a value of [`CommandParseError::MissingRequired`]
     fn missing_required(&self, cmd: &'static str, arg: &'static str) -> Self::Output;
 
+    /// Handle a value of [`CommandParseError::MissingFlagValue`]
+    ///
+    /// By default the error is passed to [`other`](Self::other).
+    fn missing_flag_value(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        self.other(CommandParseError::MissingFlagValue(ArgumentName { cmd, arg }).into())
+    }
+
     /// Handle a value of [`CommandParseError::BadConvert`]
     fn bad_convert(
         &self,
@@ -119,18 +189,85 @@ pub trait FoldError {
         inner: Self::Output,
     ) -> Self::Output;
 
+    /// Handle a value of [`CommandParseError::BadConvert`] for an argument
+    /// whose type implements [`ValueHint`](crate::ValueHint), where `value`
+    /// holds the input and the values that could have been given instead
+    ///
+    /// By default the value is discarded and the error is passed to
+    /// [`bad_convert`](Self::bad_convert).
+    fn bad_value(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+        value: ArgumentValue,
+    ) -> Self::Output {
+        let _ = value;
+        self.bad_convert(cmd, arg, inner)
+    }
+
     /// Handle a value of [`CommandParseError::Trailing`]
     fn trailing(&self, cmd: &'static str, extra: String) -> Self::Output;
 
     /// Handle a value of [`CommandParseError::Subcommand`]
     fn subcommand(&self, subcmd: &'static str, inner: Self::Output) -> Self::Output;
 
+    /// Handle the usage given by [`FlatParseError::usage`], where `parents`
+    /// holds the IDs of the commands enclosing the one that failed to parse
+    ///
+    /// By default the usage is discarded.
+    fn usage(
+        &self,
+        parents: &[&'static str],
+        usage: &'static CommandUsage,
+        inner: Self::Output,
+    ) -> Self::Output {
+        let _ = (parents, usage);
+        inner
+    }
+
+    /// Handle an error raised while parsing the command at `path`, as given by
+    /// [`FlatParseError::path`]
+    ///
+    /// By default the path is discarded.
+    fn command_path(&self, path: &[&'static str], inner: Self::Output) -> Self::Output {
+        let _ = path;
+        inner
+    }
+
+    /// Handle a value of [`CommandParseError::Spanned`]
+    ///
+    /// By default the span is discarded.
+    fn spanned(&self, span: Span, inner: Self::Output) -> Self::Output {
+        let _ = span;
+        inner
+    }
+
     /// Handle an error that couldn't be downcast to a `docbot` error
     fn other(&self, error: Anyhow) -> Self::Output;
+
+    /// Select the names from `available` to suggest in place of an
+    /// unrecognized ID, as described by [`Suggester::suggest`]
+    ///
+    /// When called for an [`IdParseError::NoMatch`], `canonical` is empty and
+    /// each name is considered on its own.  By default nothing is suggested.
+    fn suggest(
+        &self,
+        given: &str,
+        available: &'static [&'static str],
+        canonical: &'static [&'static str],
+    ) -> Vec<&'static str> {
+        let _ = (given, available, canonical);
+        vec![]
+    }
 }
 
 /// A basic implementation of [`FoldError`] that outputs a string describing the
 /// error.
+///
+/// Unrecognized command IDs are answered with suggestions from the
+/// [`DefaultSuggester`].  To use a different [`Suggester`], or to point users
+/// to a help command, see [`SuggestingFoldError`].
 #[derive(Debug, Clone, Copy)]
 pub struct SimpleFoldError;
 
@@ -148,49 +285,104 @@ impl SimpleFoldError {
                 write!(w, ", ")?;
             }
 
-            write!(w, "'{}'", opt)
+            write!(w, "'{opt}'")
         })
     }
-}
 
-#[cfg(feature = "strsim")]
-use crate::did_you_mean;
+    /// Format the usage line of a command, e.g. `foo <bar> [baz]`, prefixed
+    /// by the IDs of its parent commands
+    ///
+    /// # Errors
+    /// This function fails if `w` throws an error when writing.
+    pub fn write_usage(
+        mut w: impl Write,
+        parents: &[&str],
+        usage: &'static CommandUsage,
+    ) -> fmt::Result {
+        parents.iter().try_for_each(|p| write!(w, "{p} "))?;
+        SimpleFoldHelp::write_command_ids(&mut w, usage.ids.iter())?;
 
-/// Stub out did_you_mean when not available
-#[cfg(not(feature = "strsim"))]
-fn did_you_mean(_: impl std::any::Any, _: impl std::any::Any) -> std::iter::Empty<String> {
-    std::iter::empty()
-}
+        usage
+            .args
+            .iter()
+            .try_for_each(|arg| write!(w, " {}", SimpleFoldHelp.fold_argument_usage(arg)?))
+    }
 
-impl FoldError for SimpleFoldError {
-    type Output = Result<String, fmt::Error>;
+    /// Make suggestions for unrecognized command IDs using `suggester` instead
+    /// of the [`DefaultSuggester`]
+    pub fn with_suggester<S: Suggester>(self, suggester: S) -> SuggestingFoldError<S> {
+        SuggestingFoldError {
+            suggester,
+            help_command: None,
+        }
+    }
 
-    fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output {
-        let mut s = String::new();
+    /// Refer users to the given help command, as described by
+    /// [`SuggestingFoldError::with_help_command`]
+    #[must_use]
+    pub fn with_help_command(self, help_command: Option<&'static str>) -> SuggestingFoldError {
+        SuggestingFoldError::default().with_help_command(help_command)
+    }
+
+    fn bad_value_with(
+        msg: Result<String, fmt::Error>,
+        suggestions: Vec<&'static str>,
+    ) -> Result<String, fmt::Error> {
+        let mut s = msg?;
+
+        if !suggestions.is_empty() {
+            s.push_str("  Did you mean: ");
 
-        write!(s, "Not sure what you mean by {:?}.", given)?;
+            SimpleFoldError::write_options(&mut s, suggestions)?;
+        }
+
+        Ok(s)
+    }
 
-        let mut dym = did_you_mean(given, available).peekable();
+    fn no_id_match_with(
+        given: &str,
+        available: &'static [&'static str],
+        suggestions: Vec<&'static str>,
+    ) -> Result<String, fmt::Error> {
+        let mut s = String::new();
+
+        write!(s, "Not sure what you mean by {given:?}.")?;
 
-        if dym.peek().is_some() {
+        if !suggestions.is_empty() {
             s.push_str("  Did you mean: ");
 
-            Self::write_options(&mut s, dym)?;
+            SimpleFoldError::write_options(&mut s, suggestions)?;
         } else if !available.is_empty() {
             s.push_str("  Available options are: ");
 
-            Self::write_options(&mut s, available)?;
+            SimpleFoldError::write_options(&mut s, available)?;
         }
 
         Ok(s)
     }
+}
+
+impl FoldError for SimpleFoldError {
+    type Output = Result<String, fmt::Error>;
+
+    fn unterminated_quote(&self, quote: char, _: usize) -> Self::Output {
+        Ok(format!("Missing closing {quote} quote"))
+    }
+
+    fn dangling_escape(&self, _: usize) -> Self::Output {
+        Ok("Unexpected backslash at end of input".into())
+    }
+
+    fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output {
+        Self::no_id_match_with(&given, available, self.suggest(&given, available, &[]))
+    }
 
     fn ambiguous_id(&self, possible: &'static [&'static str], given: String) -> Self::Output {
         let mut s = String::new();
 
-        write!(s, "Not sure what you mean by {:?}.  Could be: ", given)?;
+        write!(s, "Not sure what you mean by {given:?}.  Could be: ")?;
 
-        Self::write_options(&mut s, possible)?;
+        SimpleFoldError::write_options(&mut s, possible)?;
 
         Ok(s)
     }
@@ -200,24 +392,27 @@ impl FoldError for SimpleFoldError {
 
         write!(s, "Incomplete command path, expected one of: ")?;
 
-        Self::write_options(&mut s, possible)?;
+        SimpleFoldError::write_options(&mut s, possible)?;
 
         Ok(s)
     }
 
     fn trailing_path(&self, extra: String) -> Self::Output {
-        Ok(format!("Unexpected extra path argument {:?}", extra))
+        Ok(format!("Unexpected extra path argument {extra:?}"))
     }
 
     fn no_input(&self) -> Self::Output { Ok(String::new()) }
 
     fn missing_required(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
         Ok(format!(
-            "Missing required argument '{}' to command '{}'",
-            arg, cmd
+            "Missing required argument '{arg}' to command '{cmd}'"
         ))
     }
 
+    fn missing_flag_value(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        Ok(format!("Missing value for flag '{arg}' to command '{cmd}'"))
+    }
+
     fn bad_convert(
         &self,
         cmd: &'static str,
@@ -230,16 +425,331 @@ impl FoldError for SimpleFoldError {
         ))
     }
 
+    fn bad_value(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+        value: ArgumentValue,
+    ) -> Self::Output {
+        let suggestions = self.suggest(&value.given, value.values, value.canonical);
+
+        Self::bad_value_with(self.bad_convert(cmd, arg, inner), suggestions)
+    }
+
     fn trailing(&self, cmd: &'static str, extra: String) -> Self::Output {
-        Ok(format!(
-            "Unexpected extra argument {:?} to '{}'",
-            extra, cmd
-        ))
+        Ok(format!("Unexpected extra argument {extra:?} to '{cmd}'"))
     }
 
     fn subcommand(&self, subcmd: &'static str, inner: Self::Output) -> Self::Output {
         Ok(format!("Subcommand '{}' failed: {}", subcmd, inner?))
     }
 
-    fn other(&self, error: anyhow::Error) -> Self::Output { Ok(format!("{:?}", error)) }
+    fn usage(
+        &self,
+        parents: &[&'static str],
+        usage: &'static CommandUsage,
+        inner: Self::Output,
+    ) -> Self::Output {
+        let mut s = inner?;
+
+        s.push_str("\nUsage: ");
+        SimpleFoldError::write_usage(&mut s, parents, usage)?;
+
+        Ok(s)
+    }
+
+    fn other(&self, error: anyhow::Error) -> Self::Output { Ok(format!("{error:?}")) }
+
+    fn suggest(
+        &self,
+        given: &str,
+        available: &'static [&'static str],
+        canonical: &'static [&'static str],
+    ) -> Vec<&'static str> {
+        DefaultSuggester::default().suggest(given, available, canonical)
+    }
+}
+
+/// A [`SimpleFoldError`] with a configurable [`Suggester`] and help command
+///
+/// If a help command is set with [`with_help_command`](Self::with_help_command),
+/// errors folded with [`fold_flat_parse`](FoldError::fold_flat_parse) end by
+/// pointing to the help topic of the command that failed to parse.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SuggestingFoldError<S = DefaultSuggester> {
+    suggester: S,
+    help_command: Option<&'static str>,
+}
+
+impl<S> SuggestingFoldError<S> {
+    /// Replace the [`Suggester`] used for unrecognized command IDs
+    pub fn with_suggester<T: Suggester>(self, suggester: T) -> SuggestingFoldError<T> {
+        SuggestingFoldError {
+            suggester,
+            help_command: self.help_command,
+        }
+    }
+
+    /// Set the name of the help command to refer users to, or stop referring
+    /// to it with `None`
+    #[must_use]
+    pub fn with_help_command(self, help_command: Option<&'static str>) -> Self {
+        Self {
+            help_command,
+            ..self
+        }
+    }
+}
+
+impl<S: Suggester> FoldError for SuggestingFoldError<S> {
+    type Output = Result<String, fmt::Error>;
+
+    fn unterminated_quote(&self, quote: char, start: usize) -> Self::Output {
+        SimpleFoldError.unterminated_quote(quote, start)
+    }
+
+    fn dangling_escape(&self, pos: usize) -> Self::Output { SimpleFoldError.dangling_escape(pos) }
+
+    fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output {
+        SimpleFoldError::no_id_match_with(&given, available, self.suggest(&given, available, &[]))
+    }
+
+    fn ambiguous_id(&self, possible: &'static [&'static str], given: String) -> Self::Output {
+        SimpleFoldError.ambiguous_id(possible, given)
+    }
+
+    fn incomplete_path(&self, possible: &'static [&'static str]) -> Self::Output {
+        SimpleFoldError.incomplete_path(possible)
+    }
+
+    fn trailing_path(&self, extra: String) -> Self::Output { SimpleFoldError.trailing_path(extra) }
+
+    fn no_input(&self) -> Self::Output { SimpleFoldError.no_input() }
+
+    fn missing_required(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        SimpleFoldError.missing_required(cmd, arg)
+    }
+
+    fn missing_flag_value(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        SimpleFoldError.missing_flag_value(cmd, arg)
+    }
+
+    fn bad_convert(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+    ) -> Self::Output {
+        SimpleFoldError.bad_convert(cmd, arg, inner)
+    }
+
+    fn bad_value(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+        value: ArgumentValue,
+    ) -> Self::Output {
+        let suggestions = self.suggest(&value.given, value.values, value.canonical);
+
+        SimpleFoldError::bad_value_with(self.bad_convert(cmd, arg, inner), suggestions)
+    }
+
+    fn trailing(&self, cmd: &'static str, extra: String) -> Self::Output {
+        SimpleFoldError.trailing(cmd, extra)
+    }
+
+    fn subcommand(&self, subcmd: &'static str, inner: Self::Output) -> Self::Output {
+        SimpleFoldError.subcommand(subcmd, inner)
+    }
+
+    fn usage(
+        &self,
+        parents: &[&'static str],
+        usage: &'static CommandUsage,
+        inner: Self::Output,
+    ) -> Self::Output {
+        SimpleFoldError.usage(parents, usage, inner)
+    }
+
+    fn command_path(&self, path: &[&'static str], inner: Self::Output) -> Self::Output {
+        let mut s = inner?;
+
+        // Keep the hint on the first line, before e.g. the usage of the command
+        if let Some(help) = self.help_command.filter(|_| !path.is_empty()) {
+            let end = s.find('\n').unwrap_or(s.len());
+
+            s.insert_str(end, &format!("  See `{} {}`.", help, path.join(" ")));
+        }
+
+        Ok(s)
+    }
+
+    fn other(&self, error: Anyhow) -> Self::Output { SimpleFoldError.other(error) }
+
+    fn suggest(
+        &self,
+        given: &str,
+        available: &'static [&'static str],
+        canonical: &'static [&'static str],
+    ) -> Vec<&'static str> {
+        self.suggester.suggest(given, available, canonical)
+    }
+}
+
+/// A wrapper around another [`FoldError`] implementation that annotates parse
+/// errors with the offending input and a caret underline pointing at the span
+/// the error occurred at
+///
+/// Errors without span information are passed through unchanged.
+#[derive(Debug, Clone, Copy)]
+pub struct CaretFoldError<'a, F> {
+    input: &'a str,
+    inner: &'a F,
+}
+
+impl<'a, F> CaretFoldError<'a, F> {
+    /// Wrap `inner`, annotating errors with spans into `input`
+    #[must_use]
+    pub fn new(input: &'a str, inner: &'a F) -> Self { Self { input, inner } }
+
+    /// Write `input` followed by a line of carets underlining `span`
+    ///
+    /// # Errors
+    /// This function fails if `w` throws an error when writing.
+    pub fn write_caret(mut w: impl Write, input: &str, span: &Span) -> fmt::Result {
+        let input = input.trim_end_matches(&['\r', '\n'][..]);
+        let start = span.start.min(input.len());
+        let end = span.end.clamp(start, input.len());
+
+        let col = input.get(..start).map_or(start, |s| s.chars().count());
+        let len = input
+            .get(start..end)
+            .map_or(0, |s| s.chars().count())
+            .max(1);
+
+        writeln!(w, "  {input}")?;
+        write!(w, "  {:col$}{:^<len$}", "", "", col = col, len = len)
+    }
+
+    fn annotate(&self, msg: Result<String, fmt::Error>, span: Option<Span>) -> F::Output
+    where
+        F: FoldError<Output = Result<String, fmt::Error>>,
+    {
+        let mut s = msg?;
+
+        if let Some(span) = span {
+            s.push('\n');
+            Self::write_caret(&mut s, self.input, &span)?;
+        }
+
+        Ok(s)
+    }
+}
+
+impl<F: FoldError<Output = Result<String, fmt::Error>>> FoldError for CaretFoldError<'_, F> {
+    type Output = Result<String, fmt::Error>;
+
+    fn fold_tokenize(&self, err: TokenizeError) -> Self::Output {
+        let span = match err {
+            TokenizeError::UnterminatedQuote(_, start) => start..self.input.len(),
+            TokenizeError::DanglingEscape(pos) => pos..pos + 1,
+        };
+
+        self.annotate(self.inner.fold_tokenize(err), Some(span))
+    }
+
+    fn fold_command_parse(&self, err: CommandParseError) -> Self::Output {
+        let span = err.span().cloned();
+
+        self.annotate(self.inner.fold_command_parse(err), span)
+    }
+
+    fn fold_flat_parse(&self, err: FlatParseError) -> Self::Output {
+        let span = err.error.span().cloned();
+
+        self.annotate(self.inner.fold_flat_parse(err), span)
+    }
+
+    fn unterminated_quote(&self, quote: char, start: usize) -> Self::Output {
+        self.inner.unterminated_quote(quote, start)
+    }
+
+    fn dangling_escape(&self, pos: usize) -> Self::Output { self.inner.dangling_escape(pos) }
+
+    fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output {
+        self.inner.no_id_match(given, available)
+    }
+
+    fn ambiguous_id(&self, possible: &'static [&'static str], given: String) -> Self::Output {
+        self.inner.ambiguous_id(possible, given)
+    }
+
+    fn incomplete_path(&self, possible: &'static [&'static str]) -> Self::Output {
+        self.inner.incomplete_path(possible)
+    }
+
+    fn trailing_path(&self, extra: String) -> Self::Output { self.inner.trailing_path(extra) }
+
+    fn no_input(&self) -> Self::Output { self.inner.no_input() }
+
+    fn missing_required(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        self.inner.missing_required(cmd, arg)
+    }
+
+    fn missing_flag_value(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        self.inner.missing_flag_value(cmd, arg)
+    }
+
+    fn bad_convert(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+    ) -> Self::Output {
+        self.inner.bad_convert(cmd, arg, inner)
+    }
+
+    fn bad_value(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+        value: ArgumentValue,
+    ) -> Self::Output {
+        self.inner.bad_value(cmd, arg, inner, value)
+    }
+
+    fn trailing(&self, cmd: &'static str, extra: String) -> Self::Output {
+        self.inner.trailing(cmd, extra)
+    }
+
+    fn subcommand(&self, subcmd: &'static str, inner: Self::Output) -> Self::Output {
+        self.inner.subcommand(subcmd, inner)
+    }
+
+    fn usage(
+        &self,
+        parents: &[&'static str],
+        usage: &'static CommandUsage,
+        inner: Self::Output,
+    ) -> Self::Output {
+        self.inner.usage(parents, usage, inner)
+    }
+
+    fn command_path(&self, path: &[&'static str], inner: Self::Output) -> Self::Output {
+        self.inner.command_path(path, inner)
+    }
+
+    fn other(&self, error: Anyhow) -> Self::Output { self.inner.other(error) }
+
+    fn suggest(
+        &self,
+        given: &str,
+        available: &'static [&'static str],
+        canonical: &'static [&'static str],
+    ) -> Vec<&'static str> {
+        self.inner.suggest(given, available, canonical)
+    }
 }
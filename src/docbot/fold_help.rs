@@ -1,9 +1,10 @@
-use std::{fmt, fmt::Write};
+use std::{borrow::Cow, fmt, fmt::Write, sync::LazyLock};
 
-use lazy_static::lazy_static;
 use regex::Regex;
 
-use super::{ArgumentDesc, ArgumentUsage, CommandDesc, CommandUsage, HelpTopic};
+use super::{
+    ArgumentDesc, ArgumentUsage, CommandDesc, CommandUsage, FlagUsage, HelpTopic, MessageKey,
+};
 
 /// Helper trait for processing and formatting help topics from `docbot`
 pub trait FoldHelp {
@@ -13,33 +14,50 @@ pub trait FoldHelp {
     /// Handle a raw [`ArgumentUsage`] struct
     #[inline]
     fn fold_argument_usage(&self, usage: &'static ArgumentUsage) -> Self::Output {
-        self.argument_usage(usage.name, usage.is_required, usage.is_rest)
+        match usage.flag {
+            Some(FlagUsage { short, long, value }) => {
+                self.flag_usage(usage.name, short, long, value)
+            },
+            None => self.argument_usage(usage.name, usage.is_required, usage.is_rest),
+        }
     }
 
     /// Handle a raw [`CommandUsage`] struct
     #[inline]
     fn fold_command_usage(&self, usage: &'static CommandUsage, long: bool) -> Self::Output {
-        self.command_usage(
+        let ret = self.command_usage(
             usage.ids,
             usage.args.iter().map(|a| self.fold_argument_usage(a)),
-            usage.desc,
+            &self.localize_text(usage.desc_key, usage.desc),
             long,
-        )
+        );
+
+        match usage.deprecated {
+            Some(note) => self.deprecated_usage(ret, note, long),
+            None => ret,
+        }
     }
 
     /// Handle a raw [`ArgumentDesc`] struct
     #[inline]
     fn fold_argument_desc(&self, desc: &'static ArgumentDesc) -> Self::Output {
-        self.argument_desc(desc.name, desc.is_required, desc.desc)
+        self.argument_details(desc, &self.localize_text(desc.desc_key, desc.desc))
     }
 
     /// Handle a raw [`CommandDesc`] struct
     #[inline]
     fn fold_command_desc(&self, desc: &'static CommandDesc) -> Self::Output {
+        let summary = desc
+            .summary
+            .map(|s| self.localize_text(desc.summary_key, s));
+        let examples = desc
+            .examples
+            .map(|e| self.localize_text(desc.examples_key, e));
+
         self.command_desc(
-            desc.summary,
+            summary.as_deref(),
             desc.args.iter().map(|a| self.fold_argument_desc(a)),
-            desc.examples,
+            examples.as_deref(),
         )
     }
 
@@ -50,21 +68,49 @@ pub trait FoldHelp {
                 self.fold_command_usage(usage, true),
                 self.fold_command_desc(desc),
             ),
-            HelpTopic::CommandSet(summary, commands) => self.command_set_topic(
-                *summary,
-                commands.iter().map(|c| self.fold_command_usage(c, false)),
+            HelpTopic::CommandSet(summary, key, commands) => self.command_set_topic(
+                summary.map(|s| self.localize_text(*key, s)).as_deref(),
+                commands
+                    .iter()
+                    .filter(|c| self.show_command(c))
+                    .map(|c| self.fold_command_usage(c, false)),
             ),
             HelpTopic::Custom(topic) => self.custom_topic(topic),
         }
     }
 
+    /// Decide whether a command should be listed in a command set
+    ///
+    /// By default all commands are shown.  This can be overridden, for
+    /// instance to hide commands based on their
+    /// [`permissions`](CommandUsage::permissions); see [`PermissionFoldHelp`].
+    #[inline]
+    fn show_command(&self, usage: &'static CommandUsage) -> bool {
+        let _ = usage;
+        true
+    }
+
+    /// Look up the text to display for a piece of documentation, given its
+    /// message key and the text of its doc comment
+    ///
+    /// By default the doc comment is used as-is.  See [`LocalizedFold`] for an
+    /// implementation that looks up translations using [`Localize`].
+    ///
+    /// [`LocalizedFold`]: crate::LocalizedFold
+    /// [`Localize`]: crate::Localize
+    #[inline]
+    fn localize_text(&self, key: MessageKey, text: &'static str) -> Cow<'static, str> {
+        let _ = key;
+        Cow::Borrowed(text)
+    }
+
     /// Handle a value of [`HelpTopic::Command`]
     fn command_topic(&self, usage: Self::Output, desc: Self::Output) -> Self::Output;
 
     /// Handle a value of [`HelpTopic::CommandSet`]
     fn command_set_topic(
         &self,
-        summary: Option<&'static str>,
+        summary: Option<&str>,
         commands: impl IntoIterator<Item = Self::Output>,
     ) -> Self::Output;
 
@@ -74,6 +120,22 @@ pub trait FoldHelp {
     /// Handle an argument within a command's usage line
     fn argument_usage(&self, name: &'static str, is_required: bool, is_rest: bool) -> Self::Output;
 
+    /// Handle a flag within a command's usage line
+    ///
+    /// By default flags are passed to
+    /// [`argument_usage`](Self::argument_usage) as optional arguments.
+    #[inline]
+    fn flag_usage(
+        &self,
+        name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+    ) -> Self::Output {
+        let _ = (short, long, value);
+        self.argument_usage(name, false, false)
+    }
+
     /// Handle the usage line for a command
     ///
     /// `long` is a hint indicating whether the output value should be made
@@ -82,24 +144,71 @@ pub trait FoldHelp {
         &self,
         ids: &'static [&'static str],
         args: impl IntoIterator<Item = Self::Output>,
-        desc: &'static str,
+        desc: &str,
         long: bool,
     ) -> Self::Output;
 
+    /// Annotate the usage line of a deprecated command with its deprecation
+    /// notice
+    ///
+    /// `long` carries the same meaning as in
+    /// [`command_usage`](Self::command_usage).  By default the usage line is
+    /// returned unchanged.
+    #[inline]
+    fn deprecated_usage(
+        &self,
+        usage: Self::Output,
+        note: &'static str,
+        long: bool,
+    ) -> Self::Output {
+        let _ = (note, long);
+        usage
+    }
+
+    /// Handle an argument description line from a command description, given
+    /// the raw [`ArgumentDesc`] and its (possibly localized) description text
+    ///
+    /// Override this to display details such as the argument's
+    /// [`default`](ArgumentDesc::default) value or its
+    /// [`choices`](ArgumentDesc::choices).  By default flags are passed to
+    /// [`flag_desc`](Self::flag_desc) and other arguments to
+    /// [`argument_desc`](Self::argument_desc).
+    #[inline]
+    fn argument_details(&self, desc: &'static ArgumentDesc, text: &str) -> Self::Output {
+        match desc.flag {
+            Some(FlagUsage { short, long, value }) => {
+                self.flag_desc(desc.name, short, long, value, text)
+            },
+            None => self.argument_desc(desc.name, desc.is_required, text),
+        }
+    }
+
     /// Handle an argument description line from a command description
-    fn argument_desc(
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> Self::Output;
+
+    /// Handle a flag description line from a command description
+    ///
+    /// By default flags are passed to [`argument_desc`](Self::argument_desc)
+    /// as optional arguments.
+    #[inline]
+    fn flag_desc(
         &self,
         name: &'static str,
-        is_required: bool,
-        desc: &'static str,
-    ) -> Self::Output;
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+        desc: &str,
+    ) -> Self::Output {
+        let _ = (short, long, value);
+        self.argument_desc(name, false, desc)
+    }
 
     /// Handle the description blocks for a command
     fn command_desc(
         &self,
-        summary: Option<&'static str>,
+        summary: Option<&str>,
         args: impl IntoIterator<Item = Self::Output>,
-        examples: Option<&'static str>,
+        examples: Option<&str>,
     ) -> Self::Output;
 }
 
@@ -117,9 +226,7 @@ impl SimpleFoldHelp {
         mut w: impl Write,
         ids: impl IntoIterator<IntoIter = I>,
     ) -> fmt::Result {
-        lazy_static! {
-            static ref NON_WORD_RE: Regex = Regex::new(r"\s").unwrap();
-        }
+        static NON_WORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s").unwrap());
 
         let mut ids = ids.into_iter().peekable();
         let paren = ids.len() != 1 || {
@@ -145,6 +252,71 @@ impl SimpleFoldHelp {
 
         Ok(())
     }
+
+    /// Format the names and value placeholder of a flag, using `sep` to
+    /// separate the short and long names
+    ///
+    /// # Errors
+    /// This function fails if `w` throws an error when writing.
+    pub fn write_flag(
+        mut w: impl Write,
+        short: Option<&str>,
+        long: Option<&str>,
+        value: Option<&str>,
+        sep: &str,
+    ) -> fmt::Result {
+        if let Some(short) = short {
+            write!(w, "-{short}")?;
+
+            if long.is_some() {
+                w.write_str(sep)?;
+            }
+        }
+
+        if let Some(long) = long {
+            write!(w, "--{long}")?;
+        }
+
+        if let Some(value) = value {
+            write!(w, " <{value}>")?;
+        }
+
+        Ok(())
+    }
+
+    /// Format the values accepted by an argument, if any, followed by a
+    /// separator from its description
+    ///
+    /// # Errors
+    /// This function fails if `w` throws an error when writing.
+    pub fn write_choices(mut w: impl Write, choices: Option<&[&str]>) -> fmt::Result {
+        match choices {
+            Some(choices) if !choices.is_empty() => write!(w, "one of {}. ", choices.join("|")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Finish an argument description line whose name or flags have been
+    /// written to `s`
+    fn finish_desc(
+        mut s: String,
+        optional: bool,
+        default: Option<&str>,
+        choices: Option<&[&str]>,
+        desc: &str,
+    ) -> Result<String, fmt::Error> {
+        if let Some(default) = default {
+            write!(s, " (default: {default})")?;
+        } else if optional {
+            s.push_str(" (optional)");
+        }
+
+        s.push_str(": ");
+        Self::write_choices(&mut s, choices)?;
+        s.push_str(desc);
+
+        Ok(s)
+    }
 }
 
 impl FoldHelp for SimpleFoldHelp {
@@ -169,7 +341,7 @@ impl FoldHelp for SimpleFoldHelp {
 
     fn command_set_topic(
         &self,
-        summary: Option<&'static str>,
+        summary: Option<&str>,
         commands: impl IntoIterator<Item = Self::Output>,
     ) -> Self::Output {
         let mut s = String::new();
@@ -210,11 +382,27 @@ impl FoldHelp for SimpleFoldHelp {
         Ok(s)
     }
 
+    fn flag_usage(
+        &self,
+        _name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+    ) -> Self::Output {
+        let mut s = String::new();
+
+        s.push('[');
+        Self::write_flag(&mut s, short, long, value, "|")?;
+        s.push(']');
+
+        Ok(s)
+    }
+
     fn command_usage(
         &self,
         ids: &'static [&'static str],
         args: impl IntoIterator<Item = Self::Output>,
-        desc: &'static str,
+        desc: &str,
         long: bool,
     ) -> Self::Output {
         let mut s = String::new();
@@ -245,35 +433,454 @@ impl FoldHelp for SimpleFoldHelp {
         Ok(s)
     }
 
-    fn argument_desc(
+    fn deprecated_usage(
+        &self,
+        usage: Self::Output,
+        note: &'static str,
+        long: bool,
+    ) -> Self::Output {
+        let usage = usage?;
+
+        Ok(if long {
+            format!("{usage}\nDEPRECATED: {note}")
+        } else {
+            format!("{usage} (deprecated)")
+        })
+    }
+
+    fn argument_details(&self, desc: &'static ArgumentDesc, text: &str) -> Self::Output {
+        let mut s = String::new();
+
+        match desc.flag {
+            Some(FlagUsage { short, long, value }) => {
+                Self::write_flag(&mut s, short, long, value, ", ")?;
+            },
+            None => s.push_str(desc.name),
+        }
+
+        let optional = desc.flag.is_none() && !desc.is_required;
+
+        Self::finish_desc(s, optional, desc.default, desc.choices().as_deref(), text)
+    }
+
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> Self::Output {
+        Self::finish_desc(name.to_owned(), !is_required, None, None, desc)
+    }
+
+    fn flag_desc(
+        &self,
+        _name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+        desc: &str,
+    ) -> Self::Output {
+        let mut s = String::new();
+
+        Self::write_flag(&mut s, short, long, value, ", ")?;
+
+        Self::finish_desc(s, false, None, None, desc)
+    }
+
+    fn command_desc(
+        &self,
+        summary: Option<&str>,
+        args: impl IntoIterator<Item = Self::Output>,
+        examples: Option<&str>,
+    ) -> Self::Output {
+        let mut s = String::new();
+
+        if let Some(summary) = summary {
+            write!(s, "SUMMARY\n{summary}")?;
+        }
+
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().is_some() {
+            if !s.is_empty() {
+                s.push_str("\n\n");
+            }
+
+            s.push_str("ARGUMENTS");
+
+            for arg in args {
+                write!(s, "\n  {}", arg?)?;
+            }
+        }
+
+        if let Some(examples) = examples {
+            if !s.is_empty() {
+                s.push_str("\n\n");
+            }
+
+            write!(s, "EXAMPLES\n\n{examples}")?;
+        }
+
+        Ok(s)
+    }
+}
+
+/// A wrapper around another [`FoldHelp`] implementation that hides commands
+/// requiring permissions the caller does not have
+pub struct PermissionFoldHelp<F, P> {
+    inner: F,
+    has_permission: P,
+}
+
+impl<F: fmt::Debug, P> fmt::Debug for PermissionFoldHelp<F, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PermissionFoldHelp")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FoldHelp, P: Fn(&str) -> bool> PermissionFoldHelp<F, P> {
+    /// Wrap `inner`, showing only commands for which `has_permission` returns
+    /// true for every required permission
+    pub fn new(inner: F, has_permission: P) -> Self {
+        Self {
+            inner,
+            has_permission,
+        }
+    }
+}
+
+impl<F: FoldHelp, P: Fn(&str) -> bool> FoldHelp for PermissionFoldHelp<F, P> {
+    type Output = F::Output;
+
+    fn show_command(&self, usage: &'static CommandUsage) -> bool {
+        usage.permissions.iter().all(|p| (self.has_permission)(p)) && self.inner.show_command(usage)
+    }
+
+    fn localize_text(&self, key: MessageKey, text: &'static str) -> Cow<'static, str> {
+        self.inner.localize_text(key, text)
+    }
+
+    fn command_topic(&self, usage: Self::Output, desc: Self::Output) -> Self::Output {
+        self.inner.command_topic(usage, desc)
+    }
+
+    fn command_set_topic(
+        &self,
+        summary: Option<&str>,
+        commands: impl IntoIterator<Item = Self::Output>,
+    ) -> Self::Output {
+        self.inner.command_set_topic(summary, commands)
+    }
+
+    fn custom_topic(&self, topic: &'static str) -> Self::Output { self.inner.custom_topic(topic) }
+
+    fn argument_usage(&self, name: &'static str, is_required: bool, is_rest: bool) -> Self::Output {
+        self.inner.argument_usage(name, is_required, is_rest)
+    }
+
+    fn flag_usage(
         &self,
         name: &'static str,
-        is_required: bool,
-        desc: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+    ) -> Self::Output {
+        self.inner.flag_usage(name, short, long, value)
+    }
+
+    fn command_usage(
+        &self,
+        ids: &'static [&'static str],
+        args: impl IntoIterator<Item = Self::Output>,
+        desc: &str,
+        long: bool,
+    ) -> Self::Output {
+        self.inner.command_usage(ids, args, desc, long)
+    }
+
+    fn deprecated_usage(
+        &self,
+        usage: Self::Output,
+        note: &'static str,
+        long: bool,
+    ) -> Self::Output {
+        self.inner.deprecated_usage(usage, note, long)
+    }
+
+    fn argument_details(&self, desc: &'static ArgumentDesc, text: &str) -> Self::Output {
+        self.inner.argument_details(desc, text)
+    }
+
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> Self::Output {
+        self.inner.argument_desc(name, is_required, desc)
+    }
+
+    fn flag_desc(
+        &self,
+        name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+        desc: &str,
+    ) -> Self::Output {
+        self.inner.flag_desc(name, short, long, value, desc)
+    }
+
+    fn command_desc(
+        &self,
+        summary: Option<&str>,
+        args: impl IntoIterator<Item = Self::Output>,
+        examples: Option<&str>,
+    ) -> Self::Output {
+        self.inner.command_desc(summary, args, examples)
+    }
+}
+
+/// An implementation of [`FoldHelp`] outputting Markdown, suitable for chat
+/// clients such as Discord
+///
+/// Usage lines are rendered as code spans, section headers in bold, and
+/// arguments and commands as bulleted lists.  Examples are placed in a code
+/// block.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownFoldHelp {
+    escape: bool,
+}
+
+impl MarkdownFoldHelp {
+    /// Construct a new `MarkdownFoldHelp` which outputs descriptions verbatim
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Set whether Markdown metacharacters in descriptions and summaries should
+    /// be escaped
+    ///
+    /// This is useful if doc comments are written as plain text and should not
+    /// be interpreted as Markdown.
+    #[must_use]
+    pub fn with_escape(mut self, escape: bool) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Write `s`, escaping any Markdown metacharacters with a backslash
+    ///
+    /// # Errors
+    /// This function fails if `w` throws an error when writing.
+    pub fn write_escaped(mut w: impl Write, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '~' | '|' | '<' | '>' | '#' | '[' | ']'
+            ) {
+                w.write_char('\\')?;
+            }
+
+            w.write_char(c)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_choices(mut w: impl Write, choices: Option<&[&str]>) -> fmt::Result {
+        match choices {
+            Some(choices) if !choices.is_empty() => {
+                write!(w, "one of `{}`. ", choices.join("|"))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Finish an argument description line whose name or flags have been
+    /// written to `s`
+    fn finish_desc(
+        self,
+        mut s: String,
+        optional: bool,
+        default: Option<&str>,
+        choices: Option<&[&str]>,
+        desc: &str,
+    ) -> Result<String, fmt::Error> {
+        if let Some(default) = default {
+            write!(s, " *(default: `{default}`)*")?;
+        } else if optional {
+            s.push_str(" *(optional)*");
+        }
+
+        s.push_str(": ");
+        Self::write_choices(&mut s, choices)?;
+        self.write_text(&mut s, desc)?;
+
+        Ok(s)
+    }
+
+    fn write_flag_code(
+        mut w: impl Write,
+        short: Option<&str>,
+        long: Option<&str>,
+        value: Option<&str>,
+    ) -> fmt::Result {
+        w.write_char('`')?;
+        SimpleFoldHelp::write_flag(&mut w, short, long, value, ", ")?;
+        w.write_char('`')
+    }
+
+    fn write_text(self, mut w: impl Write, s: &str) -> fmt::Result {
+        if self.escape {
+            Self::write_escaped(w, s)
+        } else {
+            w.write_str(s)
+        }
+    }
+}
+
+impl FoldHelp for MarkdownFoldHelp {
+    type Output = Result<String, fmt::Error>;
+
+    fn command_topic(&self, usage: Self::Output, desc: Self::Output) -> Self::Output {
+        SimpleFoldHelp.command_topic(usage, desc)
+    }
+
+    fn command_set_topic(
+        &self,
+        summary: Option<&str>,
+        commands: impl IntoIterator<Item = Self::Output>,
     ) -> Self::Output {
         let mut s = String::new();
 
-        s.push_str(name);
+        if let Some(summary) = summary {
+            self.write_text(&mut s, summary)?;
+        }
 
-        if !is_required {
-            s.push_str(" (optional)");
+        let mut commands = commands.into_iter().peekable();
+
+        if commands.peek().is_some() {
+            if !s.is_empty() {
+                s.push_str("\n\n");
+            }
+
+            s.push_str("**Commands**");
+
+            for cmd in commands {
+                write!(s, "\n- {}", cmd?)?;
+            }
         }
 
-        write!(s, ": {}", desc)?;
+        Ok(s)
+    }
+
+    fn custom_topic(&self, topic: &'static str) -> Self::Output {
+        let mut s = String::new();
+
+        self.write_text(&mut s, topic)?;
 
         Ok(s)
     }
 
+    fn argument_usage(&self, name: &'static str, is_required: bool, is_rest: bool) -> Self::Output {
+        SimpleFoldHelp.argument_usage(name, is_required, is_rest)
+    }
+
+    fn flag_usage(
+        &self,
+        name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+    ) -> Self::Output {
+        SimpleFoldHelp.flag_usage(name, short, long, value)
+    }
+
+    fn command_usage(
+        &self,
+        ids: &'static [&'static str],
+        args: impl IntoIterator<Item = Self::Output>,
+        desc: &str,
+        long: bool,
+    ) -> Self::Output {
+        let mut s = String::new();
+
+        if long {
+            s.push_str("**Usage:** ");
+        }
+
+        s.push('`');
+        SimpleFoldHelp::write_command_ids(&mut s, ids.iter().copied())?;
+
+        for arg in args {
+            write!(s, " {}", arg?)?;
+        }
+
+        s.push('`');
+
+        if !desc.is_empty() {
+            s.push_str(if long { "\n" } else { ": " });
+            self.write_text(&mut s, desc)?;
+        }
+
+        Ok(s)
+    }
+
+    fn deprecated_usage(
+        &self,
+        usage: Self::Output,
+        note: &'static str,
+        long: bool,
+    ) -> Self::Output {
+        let mut s = usage?;
+
+        if long {
+            s.push_str("\n**Deprecated:** ");
+            self.write_text(&mut s, note)?;
+        } else {
+            s.push_str(" *(deprecated)*");
+        }
+
+        Ok(s)
+    }
+
+    fn argument_details(&self, desc: &'static ArgumentDesc, text: &str) -> Self::Output {
+        let mut s = String::new();
+
+        match desc.flag {
+            Some(FlagUsage { short, long, value }) => {
+                Self::write_flag_code(&mut s, short, long, value)?;
+            },
+            None => write!(s, "`{}`", desc.name)?,
+        }
+
+        let optional = desc.flag.is_none() && !desc.is_required;
+
+        self.finish_desc(s, optional, desc.default, desc.choices().as_deref(), text)
+    }
+
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> Self::Output {
+        self.finish_desc(format!("`{name}`"), !is_required, None, None, desc)
+    }
+
+    fn flag_desc(
+        &self,
+        _name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+        desc: &str,
+    ) -> Self::Output {
+        let mut s = String::new();
+
+        Self::write_flag_code(&mut s, short, long, value)?;
+
+        self.finish_desc(s, false, None, None, desc)
+    }
+
     fn command_desc(
         &self,
-        summary: Option<&'static str>,
+        summary: Option<&str>,
         args: impl IntoIterator<Item = Self::Output>,
-        examples: Option<&'static str>,
+        examples: Option<&str>,
     ) -> Self::Output {
         let mut s = String::new();
 
         if let Some(summary) = summary {
-            write!(s, "SUMMARY\n{}", summary)?;
+            s.push_str("**Summary**\n");
+            self.write_text(&mut s, summary)?;
         }
 
         let mut args = args.into_iter().peekable();
@@ -283,10 +890,10 @@ impl FoldHelp for SimpleFoldHelp {
                 s.push_str("\n\n");
             }
 
-            s.push_str("ARGUMENTS");
+            s.push_str("**Arguments**");
 
             for arg in args {
-                write!(s, "\n  {}", arg?)?;
+                write!(s, "\n- {}", arg?)?;
             }
         }
 
@@ -295,7 +902,7 @@ impl FoldHelp for SimpleFoldHelp {
                 s.push_str("\n\n");
             }
 
-            write!(s, "EXAMPLES\n\n{}", examples)?;
+            write!(s, "**Examples**\n```\n{examples}\n```")?;
         }
 
         Ok(s)
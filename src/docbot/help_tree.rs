@@ -0,0 +1,54 @@
+use super::{CommandPath, Help, HelpTopic, PathParseError};
+
+/// The help topics for a command and all of its subcommands
+///
+/// With the `serde` feature enabled, this can be serialized to produce a
+/// machine-readable schema of an entire command tree.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HelpTree {
+    /// The IDs leading to this topic from the root command, empty for the root
+    pub path: Vec<&'static str>,
+    /// The help topic for this command
+    pub topic: &'static HelpTopic,
+    /// The trees of any commands listed by this topic
+    pub commands: Vec<HelpTree>,
+}
+
+impl HelpTree {
+    /// Collect the help topics for `C` and all of its subcommands
+    ///
+    /// # Errors
+    /// This function fails if a command ID listed in a help topic cannot be
+    /// parsed as a path to that command.
+    pub fn new<C: Help>() -> Result<Self, PathParseError> {
+        Self::walk::<C>(vec![], C::help(None::<C::Path>))
+    }
+
+    fn walk<C: Help>(
+        path: Vec<&'static str>,
+        topic: &'static HelpTopic,
+    ) -> Result<Self, PathParseError> {
+        let commands = match topic {
+            HelpTopic::CommandSet(_, _, usages) => usages
+                .iter()
+                .filter_map(|u| u.ids.first())
+                .map(|id| {
+                    let mut path = path.clone();
+                    path.push(id);
+
+                    let topic = C::help(Some(C::Path::parse(&path)?));
+
+                    Self::walk::<C>(path, topic)
+                })
+                .collect::<Result<_, _>>()?,
+            HelpTopic::Command(..) | HelpTopic::Custom(_) => vec![],
+        };
+
+        Ok(Self {
+            path,
+            topic,
+            commands,
+        })
+    }
+}
@@ -13,22 +13,65 @@ use std::{
 
 use thiserror::Error;
 
+mod complete;
 #[cfg(feature = "strsim")]
 mod did_you_mean;
+mod dispatch;
 mod fold_error;
 mod fold_help;
+mod help_tree;
+mod localize;
+#[cfg(feature = "unicode")]
+mod normalize;
+pub mod repl;
+mod suggest;
 mod tokenize;
+mod value_hint;
 
+pub use complete::{Complete, Completion};
 #[cfg(feature = "strsim")]
 pub use did_you_mean::did_you_mean;
-pub use fold_error::{Downcast, FoldError, SimpleFoldError};
-pub use fold_help::{FoldHelp, SimpleFoldHelp};
-pub use tokenize::tokenize_str_simple;
+pub use dispatch::{AsyncDispatch, Dispatch, SendAsyncDispatch};
+pub use fold_error::{CaretFoldError, Downcast, FoldError, SimpleFoldError, SuggestingFoldError};
+pub use fold_help::{FoldHelp, MarkdownFoldHelp, PermissionFoldHelp, SimpleFoldHelp};
+pub use help_tree::HelpTree;
+pub use localize::{Localize, LocalizedFold};
+#[cfg(feature = "unicode")]
+pub use normalize::Normalization;
+#[cfg(feature = "strsim")]
+pub use suggest::{DamerauLevenshtein, JaroWinkler};
+pub use suggest::{
+    DefaultSuggester, KeyboardDistance, Metric, MetricSuggester, NoSuggestions, Phonetic, Suggester,
+};
+pub use tokenize::{
+    tokenize_str, tokenize_str_simple, Span, Spanned, SpannedTokens, Token, Tokens, Unspanned,
+};
+pub use value_hint::{ArgumentValue, ValueHint};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use super::value_hint::{HasValueHint, NoValueHint, Probe};
+}
+
+/// Error type for failures when tokenizing a string
+#[derive(Error, Debug)]
+pub enum TokenizeError {
+    /// A quoted section was not closed before the end of the input
+    #[error("unterminated {0:?} quote starting at byte {1}")]
+    UnterminatedQuote(char, usize),
+    /// A backslash was found at the end of the input
+    #[error("dangling escape at byte {0}")]
+    DanglingEscape(usize),
+}
 
 /// Error type for failures when parsing a command ID
 #[derive(Error, Debug)]
 pub enum IdParseError {
     /// No IDs matched the given string
+    ///
+    /// The list contains the names that could have been given.  The canonical
+    /// name of each one's command can be found with
+    /// [`CommandId::visible_canonical_names`].
     #[error("no ID match for {0:?}")]
     NoMatch(String, &'static [&'static str]),
     /// Multiple IDs could match the given string
@@ -79,15 +122,147 @@ pub enum CommandParseError {
     /// A required argument was missing
     #[error("missing required argument {0}")]
     MissingRequired(ArgumentName),
+    /// A flag expecting a value was given as the last argument
+    #[error("missing value for flag {0}")]
+    MissingFlagValue(ArgumentName),
     /// `TryFrom::try_from` failed for an argument
+    ///
+    /// If the argument's type implements [`ValueHint`], the input and the
+    /// values that could have been given are included.
     #[error("failed to convert argument {0} from a string")]
-    BadConvert(ArgumentName, anyhow::Error),
+    BadConvert(ArgumentName, anyhow::Error, Option<ArgumentValue>),
     /// Extra arguments were provided
     #[error("trailing argument {1:?} of {0:?}")]
     Trailing(&'static str, String),
     /// A subcommand failed to parse
     #[error("failed to parse subcommand {0:?}")]
     Subcommand(&'static str, Box<CommandParseError>),
+    /// An error occurred at the given location in the input
+    #[error("{1} (at {0:?})")]
+    Spanned(Span, Box<CommandParseError>),
+}
+
+impl CommandParseError {
+    /// Annotate this error with a location in the input, if one is given
+    #[must_use]
+    pub fn with_span(self, span: Option<Span>) -> Self {
+        match span {
+            Some(span) => Self::Spanned(span, Box::new(self)),
+            None => self,
+        }
+    }
+
+    /// Find the location of this error in the input, if one is known
+    #[must_use]
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Spanned(span, _) => Some(span),
+            Self::Subcommand(_, err) => err.span(),
+            _ => None,
+        }
+    }
+
+    /// Remove any [`Subcommand`](Self::Subcommand) layers from this error,
+    /// collecting the full path of the command that failed to parse
+    ///
+    /// For example, a missing argument to `admin user ban` produces the path
+    /// `["admin", "user", "ban"]`, while an unrecognized subcommand of
+    /// `admin user` produces `["admin", "user"]`.  The location of the error
+    /// is preserved.
+    #[must_use]
+    pub fn flatten(self) -> FlatParseError {
+        let mut path = vec![];
+        let mut span = None;
+        let mut err = self;
+
+        let error = loop {
+            err = match err {
+                Self::Subcommand(id, inner) => {
+                    path.push(id);
+                    *inner
+                },
+                Self::Spanned(s, inner) if matches!(*inner, Self::Subcommand(..)) => {
+                    span = span.or(Some(s));
+                    *inner
+                },
+                err => break err,
+            };
+        };
+
+        if let Some(cmd) = error.command() {
+            path.push(cmd);
+        }
+
+        let error = if error.span().is_some() {
+            error
+        } else {
+            error.with_span(span)
+        };
+
+        FlatParseError {
+            path,
+            error,
+            usage: None,
+        }
+    }
+
+    /// Remove any [`Spanned`](Self::Spanned) layers from this error
+    fn unspanned(&self) -> &Self {
+        match self {
+            Self::Spanned(_, err) => err.unspanned(),
+            err => err,
+        }
+    }
+
+    /// Get the ID of the command that raised this error, if it names one
+    fn command(&self) -> Option<&'static str> {
+        match self {
+            Self::MissingRequired(ArgumentName { cmd, .. })
+            | Self::MissingFlagValue(ArgumentName { cmd, .. })
+            | Self::BadConvert(ArgumentName { cmd, .. }, ..)
+            | Self::Trailing(cmd, _) => Some(*cmd),
+            Self::Spanned(_, err) => err.command(),
+            _ => None,
+        }
+    }
+}
+
+/// A [`CommandParseError`] with its [`Subcommand`](CommandParseError::Subcommand)
+/// layers removed, as returned by [`CommandParseError::flatten`]
+#[derive(Debug)]
+pub struct FlatParseError {
+    /// The canonical IDs of the commands leading to and including the command
+    /// that failed to parse
+    pub path: Vec<&'static str>,
+    /// The error raised by the last command in [`path`](Self::path)
+    pub error: CommandParseError,
+    /// The usage of the last command in [`path`](Self::path), if it was looked
+    /// up with [`with_usage`](Self::with_usage) and the error concerns the
+    /// command's arguments
+    pub usage: Option<&'static CommandUsage>,
+}
+
+impl FlatParseError {
+    /// Retrieve the help topic for the command that failed to parse, or the
+    /// top-level topic of `H` if the error was raised at the top level
+    #[must_use]
+    pub fn help_topic<H: Help>(&self) -> &'static HelpTopic {
+        H::help(H::Path::parse_opt(&self.path).ok().flatten())
+    }
+
+    /// Look up the usage of the command that failed to parse in the command
+    /// tree of `H`, if the error is a missing or extra argument
+    #[must_use]
+    pub fn with_usage<H: Help>(self) -> Self {
+        let usage = match self.error.unspanned() {
+            CommandParseError::MissingRequired(_)
+            | CommandParseError::MissingFlagValue(_)
+            | CommandParseError::Trailing(..) => H::tree().find(&self.path).map(|n| &n.usage),
+            _ => None,
+        };
+
+        Self { usage, ..self }
+    }
 }
 
 impl From<Infallible> for CommandParseError {
@@ -109,13 +284,43 @@ pub trait Command: Sized {
 
     /// Try to parse a sequence of arguments as a command
     ///
+    /// Implementors must provide at least one of this function and
+    /// [`parse_spanned`](Self::parse_spanned).  By default this calls
+    /// [`parse_spanned`](Self::parse_spanned) with arguments that have no
+    /// location.
+    ///
     /// # Errors
     /// Should return an error for syntax or command-not-found errors, or for
     /// any errors while parsing arguments.
-    fn parse<I: IntoIterator<Item = S>, S: AsRef<str>>(iter: I) -> Result<Self, CommandParseError>;
+    fn parse<I: IntoIterator<Item = S>, S: AsRef<str>>(iter: I) -> Result<Self, CommandParseError> {
+        Self::parse_spanned(iter.into_iter().map(Unspanned))
+    }
+
+    /// Try to parse a sequence of arguments as a command, annotating any
+    /// errors with the locations of the arguments that caused them
+    ///
+    /// By default this calls [`parse`](Self::parse), so errors are not
+    /// annotated.
+    ///
+    /// # Errors
+    /// Should return the same errors as [`parse`](Self::parse), wrapped in
+    /// [`CommandParseError::Spanned`] where the location of the error is known.
+    fn parse_spanned<I: IntoIterator<Item = T>, T: Token>(
+        iter: I,
+    ) -> Result<Self, CommandParseError> {
+        Self::parse(iter)
+    }
 
     /// Return an ID uniquely describing the base type of this command.
     fn id(&self) -> Self::Id;
+
+    /// Return the names of the permissions required to run this command,
+    /// including those required by any subcommands it contains
+    fn required_permissions(&self) -> Vec<&'static str> { Vec::new() }
+
+    /// Return a deprecation notice if this command, or any subcommand it
+    /// contains, is deprecated
+    fn deprecated(&self) -> Option<&'static str> { None }
 }
 
 /// A command ID, convertible to and from a string
@@ -123,8 +328,47 @@ pub trait CommandId: Copy + FromStr<Err = IdParseError> + Display {
     /// List all possible valid names that can be parsed, including aliases
     fn names() -> &'static [&'static str];
 
+    /// List the names that should be suggested to users, excluding those of
+    /// hidden commands
+    #[must_use]
+    fn visible_names() -> &'static [&'static str] { Self::names() }
+
+    /// List the canonical name of the command each of
+    /// [`visible_names`](Self::visible_names) belongs to, in the same order
+    #[must_use]
+    fn visible_canonical_names() -> &'static [&'static str] { Self::visible_names() }
+
+    /// List all possible valid names that can be parsed in the given locale,
+    /// including aliases specific to that locale
+    ///
+    /// Locales are matched against those given to `#[docbot(alias(...))]`.
+    /// If no aliases were given for a locale with a region or script subtag,
+    /// such as `de-AT`, the aliases for its parent locale are used instead.
+    #[must_use]
+    fn names_in_locale(locale: &str) -> &'static [&'static str] {
+        let _ = locale;
+        Self::names()
+    }
+
+    /// Parse a command ID, additionally accepting aliases specific to the
+    /// given locale
+    ///
+    /// Locales are resolved as in [`names_in_locale`](Self::names_in_locale).
+    /// Case-insensitive matching uses the default, locale-independent case
+    /// fold, so an alias like `yardım` is not reachable by typing `YARDIM`.
+    ///
+    /// # Errors
+    /// This function returns the same errors as [`FromStr::from_str`].
+    fn parse_in_locale(s: &str, locale: &str) -> Result<Self, IdParseError> {
+        let _ = locale;
+        s.parse()
+    }
+
     /// Get the canonical name for an ID
     fn to_str(&self) -> &'static str;
+
+    /// List all names, including aliases, that begin with the given prefix
+    fn complete(prefix: &str) -> &'static [&'static str];
 }
 
 /// A chain of command IDs representing a command or subcommand
@@ -168,7 +412,7 @@ impl<T: CommandId> CommandPath for T {
         let mut iter = iter.into_iter();
         let head = iter
             .next()
-            .ok_or_else(|| PathParseError::Incomplete(Self::Id::names()))?;
+            .ok_or_else(|| PathParseError::Incomplete(Self::Id::visible_names()))?;
 
         if let Some(s) = iter.next() {
             return Err(PathParseError::Trailing(s.as_ref().into()));
@@ -180,8 +424,21 @@ impl<T: CommandId> CommandPath for T {
     fn head(&self) -> Self::Id { *self }
 }
 
+/// Names of a flag or option argument
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FlagUsage {
+    /// The short name of the flag, without the leading `-`
+    pub short: Option<&'static str>,
+    /// The long name of the flag, without the leading `--`
+    pub long: Option<&'static str>,
+    /// The name of the value taken by the flag, if any
+    pub value: Option<&'static str>,
+}
+
 /// Usage description for an argument
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArgumentUsage {
     /// The name of the argument
     pub name: &'static str,
@@ -189,10 +446,41 @@ pub struct ArgumentUsage {
     pub is_required: bool,
     /// Whether the argument is a rest parameter
     pub is_rest: bool,
+    /// Whether the argument is a command path, from which point on flags are
+    /// no longer recognized
+    pub is_path: bool,
+    /// The names of the argument if it is a flag rather than a positional
+    /// argument
+    pub flag: Option<FlagUsage>,
+}
+
+/// Identifies a piece of localizable text, such as a line of a command's
+/// documentation
+///
+/// Keys are structured after Fluent messages: each command has a message whose
+/// value is its short description, with attributes for its summary, examples,
+/// and argument descriptions.  See [`Localize`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MessageKey {
+    /// The ID of the message
+    pub id: &'static str,
+    /// The attribute of the message, or `None` to refer to its value
+    pub attr: Option<&'static str>,
+}
+
+impl Display for MessageKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.attr {
+            Some(attr) => write!(f, "{}.{}", self.id, attr),
+            None => f.write_str(self.id),
+        }
+    }
 }
 
 /// Usage description for a command
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CommandUsage {
     /// The possible IDs of this command
     pub ids: &'static [&'static str],
@@ -200,49 +488,180 @@ pub struct CommandUsage {
     pub args: &'static [ArgumentUsage],
     /// A short description
     pub desc: &'static str,
+    /// The message key used to localize [`desc`](Self::desc)
+    pub desc_key: MessageKey,
+    /// Names of the permissions required to run this command
+    pub permissions: &'static [&'static str],
+    /// A notice explaining what to use instead, if this command is deprecated
+    pub deprecated: Option<&'static str>,
 }
 
 /// Detailed description of a command argument
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArgumentDesc {
     /// The name of the argument
     pub name: &'static str,
     /// Whether the argument is required
     pub is_required: bool,
+    /// The names of the argument if it is a flag rather than a positional
+    /// argument
+    pub flag: Option<FlagUsage>,
+    /// The argument's default value, as given by `#[docbot(default = "...")]`
+    pub default: Option<&'static str>,
     /// A detailed description of the argument
     pub desc: &'static str,
+    /// The message key used to localize [`desc`](Self::desc)
+    pub desc_key: MessageKey,
+    /// The Rust type of the argument, as written in the command's definition
+    pub ty: &'static str,
+    /// Accessor for the canonical values accepted by the argument, if its type
+    /// implements [`ValueHint`]
+    ///
+    /// Values with several aliases may be repeated; see
+    /// [`choices`](Self::choices) for a list of distinct values.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "choices", serialize_with = "serialize_value_hint")
+    )]
+    pub value_hint: fn() -> Option<&'static [&'static str]>,
+}
+
+impl ArgumentDesc {
+    /// List the distinct values accepted by this argument, if its type
+    /// implements [`ValueHint`]
+    #[must_use]
+    pub fn choices(&self) -> Option<Vec<&'static str>> { (self.value_hint)().map(distinct) }
+}
+
+/// Remove repeated values from a list, preserving the order of their first
+/// occurrences
+fn distinct(values: &'static [&'static str]) -> Vec<&'static str> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(i, v)| !values[..*i].contains(v))
+        .map(|(_, v)| *v)
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_value_hint<S: serde::Serializer>(
+    value_hint: &fn() -> Option<&'static [&'static str]>,
+    ser: S,
+) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&value_hint().map(distinct), ser)
 }
 
 /// Detailed description of a command
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CommandDesc {
     /// A detailed summary of the command's behavior
     pub summary: Option<&'static str>,
+    /// The message key used to localize [`summary`](Self::summary)
+    pub summary_key: MessageKey,
     /// Descriptions of the command's arguments
     pub args: &'static [ArgumentDesc],
     /// Example uses of the command
     pub examples: Option<&'static str>,
+    /// The message key used to localize [`examples`](Self::examples)
+    pub examples_key: MessageKey,
 }
 
 /// A generic help topic
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HelpTopic {
     /// A help topic referring to a single command
     Command(CommandUsage, CommandDesc),
     /// A help topic referring to a set of commands, prefaced by an optional
-    /// summary
-    CommandSet(Option<&'static str>, &'static [CommandUsage]),
+    /// summary and the message key used to localize it
+    CommandSet(Option<&'static str>, MessageKey, &'static [CommandUsage]),
     /// A custom help topic
     Custom(&'static str),
 }
 
+/// A static description of a set of commands and their subcommands
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CommandTree {
+    /// A summary of the command set
+    pub summary: Option<&'static str>,
+    /// The message key used to localize [`summary`](Self::summary)
+    pub summary_key: MessageKey,
+    /// The commands in this set
+    pub commands: &'static [CommandNode],
+}
+
+impl CommandTree {
+    /// Find the command reached by following the given IDs from this tree,
+    /// descending into subcommands after the first
+    #[must_use]
+    pub fn find<S: AsRef<str>>(&'static self, path: &[S]) -> Option<&'static CommandNode> {
+        let (last, parents) = path.split_last()?;
+        let tree = parents
+            .iter()
+            .try_fold(self, |t, id| t.node(id.as_ref())?.subtree())?;
+
+        tree.node(last.as_ref())
+    }
+
+    fn node(&'static self, id: &str) -> Option<&'static CommandNode> {
+        self.commands.iter().find(|n| n.usage.ids.contains(&id))
+    }
+}
+
+/// A single command within a [`CommandTree`]
+#[derive(Debug)]
+pub struct CommandNode {
+    /// Usage description for this command, including all of its IDs
+    pub usage: CommandUsage,
+    /// Detailed description of this command
+    pub desc: CommandDesc,
+    /// Whether this command is omitted from help listings and suggestions
+    ///
+    /// Hidden commands are only matched by their complete IDs.
+    pub hidden: bool,
+    /// Accessor for the tree of this command's subcommands, if it has any
+    pub subcommands: Option<fn() -> &'static CommandTree>,
+}
+
+impl CommandNode {
+    /// Retrieve the tree of this command's subcommands, if it has any
+    #[must_use]
+    pub fn subtree(&self) -> Option<&'static CommandTree> { self.subcommands.map(|f| f()) }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CommandNode {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = ser.serialize_struct("CommandNode", 4)?;
+        s.serialize_field("usage", &self.usage)?;
+        s.serialize_field("desc", &self.desc)?;
+        s.serialize_field("hidden", &self.hidden)?;
+        s.serialize_field("subcommands", &self.subtree())?;
+        s.end()
+    }
+}
+
 /// A command with associated help topics
+///
+/// To collect the help topics for all commands and subcommands at once, see
+/// [`HelpTree`] or [`tree`](Self::tree).
 pub trait Help: Command {
     /// Retrieve the help topic corresponding to the given ID.
     fn help<U: Into<Self::Path>>(topic: Option<U>) -> &'static HelpTopic;
+
+    /// Retrieve a description of every command, argument, and subcommand
+    /// reachable from this type
+    fn tree() -> &'static CommandTree;
 }
 
 /// Common traits and types used with this crate
 pub mod prelude {
-    pub use super::{Command, CommandId, Docbot, FoldError, FoldHelp, Help};
+    pub use super::{Command, CommandId, Complete, Docbot, FoldError, FoldHelp, Help};
 }
@@ -0,0 +1,431 @@
+use std::{borrow::Cow, fmt};
+
+use super::{
+    Anyhow, ArgumentDesc, ArgumentValue, CommandUsage, FoldError, FoldHelp, MessageKey,
+    SimpleFoldError, Span,
+};
+
+/// A source of translations for help text and error messages
+///
+/// Messages are identified by a [`MessageKey`], following the structure of
+/// [Fluent](https://projectfluent.org/) messages.  Each command generated by
+/// `#[derive(Docbot)]` has a message whose ID is the kebab-case name of its
+/// type, followed by the name of its variant for enums.  The value of the
+/// message is the command's short description, and its attributes are:
+///
+/// - `summary`: the command's detailed summary, or the summary of a command set
+/// - `examples`: the command's examples
+/// - `arg-<name>`: the description of the argument `<name>`
+///
+/// For example, a command `Ban` of an enum `ModCommand` could be translated
+/// into German with:
+///
+/// ```ftl
+/// mod-command-ban = Sperrt einen Benutzer
+///     .summary = Sperrt einen Benutzer dauerhaft vom Server.
+///     .arg-user = Der zu sperrende Benutzer
+/// ```
+///
+/// When wrapped in a [`LocalizedFold`], the error messages of
+/// [`SimpleFoldError`] are looked up using the following IDs, with the
+/// arguments listed:
+///
+/// | ID | Arguments |
+/// |----|-----------|
+/// | `docbot-unterminated-quote` | `quote` |
+/// | `docbot-dangling-escape` | |
+/// | `docbot-no-id-match` | `given` |
+/// | `docbot-no-id-match.did-you-mean` | `options` |
+/// | `docbot-no-id-match.available` | `options` |
+/// | `docbot-ambiguous-id` | `given`, `options` |
+/// | `docbot-incomplete-path` | `options` |
+/// | `docbot-trailing-path` | `extra` |
+/// | `docbot-missing-required` | `cmd`, `arg` |
+/// | `docbot-missing-flag-value` | `cmd`, `arg` |
+/// | `docbot-bad-convert` | `cmd`, `arg`, `error` |
+/// | `docbot-bad-convert.did-you-mean` | `options` |
+/// | `docbot-trailing` | `cmd`, `extra` |
+/// | `docbot-subcommand` | `subcmd`, `error` |
+/// | `docbot-usage` | `usage`, `error` |
+/// | `docbot-command-path` | `path`, `error` |
+///
+/// With the `fluent` feature enabled, this trait is implemented for
+/// `fluent_bundle::bundle::FluentBundle`.
+pub trait Localize {
+    /// Look up and format the message identified by `key` with the given
+    /// arguments, returning `None` if no translation is available
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String>;
+}
+
+impl<L: Localize + ?Sized> Localize for &L {
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String> {
+        (**self).localize(key, args)
+    }
+}
+
+/// Try each source of translations in order, such as a list of bundles for a
+/// user's preferred languages
+impl<L: Localize> Localize for [L] {
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String> {
+        self.iter().find_map(|l| l.localize(key, args))
+    }
+}
+
+impl<L: Localize, const N: usize> Localize for [L; N] {
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String> {
+        self[..].localize(key, args)
+    }
+}
+
+#[cfg(feature = "fluent")]
+impl<R, M> Localize for fluent_bundle::bundle::FluentBundle<R, M>
+where
+    R: std::borrow::Borrow<fluent_bundle::FluentResource>,
+    M: fluent_bundle::memoizer::MemoizerKind,
+{
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String> {
+        let msg = self.get_message(key.id)?;
+        let pattern = match key.attr {
+            Some(attr) => msg.get_attribute(attr)?.value(),
+            None => msg.value()?,
+        };
+        let args = args.iter().copied().collect::<fluent_bundle::FluentArgs>();
+
+        // Fluent substitutes a placeholder for anything it fails to resolve,
+        // so the result is still usable if errors occur
+        let mut errors = vec![];
+
+        Some(
+            self.format_pattern(pattern, Some(&args), &mut errors)
+                .into_owned(),
+        )
+    }
+}
+
+/// A wrapper around another [`FoldHelp`] or [`FoldError`] implementation that
+/// translates help text and error messages using a [`Localize`] lookup
+///
+/// Any text without a translation falls back to the output of the inner
+/// implementation, i.e. the original doc comment or English error message.
+pub struct LocalizedFold<F, L> {
+    inner: F,
+    localize: L,
+}
+
+impl<F: fmt::Debug, L> fmt::Debug for LocalizedFold<F, L> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalizedFold")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, L: Localize> LocalizedFold<F, L> {
+    /// Wrap `inner`, looking up translations with `localize`
+    pub fn new(inner: F, localize: L) -> Self { Self { inner, localize } }
+
+    fn message(
+        &self,
+        id: &'static str,
+        attr: Option<&'static str>,
+        args: &[(&str, &str)],
+    ) -> Option<String> {
+        self.localize.localize(MessageKey { id, attr }, args)
+    }
+
+    fn options(opts: impl IntoIterator<Item = impl fmt::Display>) -> Result<String, fmt::Error> {
+        let mut s = String::new();
+
+        SimpleFoldError::write_options(&mut s, opts)?;
+
+        Ok(s)
+    }
+}
+
+impl<F: FoldHelp, L: Localize> FoldHelp for LocalizedFold<F, L> {
+    type Output = F::Output;
+
+    fn show_command(&self, usage: &'static CommandUsage) -> bool { self.inner.show_command(usage) }
+
+    fn localize_text(&self, key: MessageKey, text: &'static str) -> Cow<'static, str> {
+        self.localize
+            .localize(key, &[])
+            .map_or_else(|| self.inner.localize_text(key, text), Cow::Owned)
+    }
+
+    fn command_topic(&self, usage: Self::Output, desc: Self::Output) -> Self::Output {
+        self.inner.command_topic(usage, desc)
+    }
+
+    fn command_set_topic(
+        &self,
+        summary: Option<&str>,
+        commands: impl IntoIterator<Item = Self::Output>,
+    ) -> Self::Output {
+        self.inner.command_set_topic(summary, commands)
+    }
+
+    fn custom_topic(&self, topic: &'static str) -> Self::Output { self.inner.custom_topic(topic) }
+
+    fn argument_usage(&self, name: &'static str, is_required: bool, is_rest: bool) -> Self::Output {
+        self.inner.argument_usage(name, is_required, is_rest)
+    }
+
+    fn flag_usage(
+        &self,
+        name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+    ) -> Self::Output {
+        self.inner.flag_usage(name, short, long, value)
+    }
+
+    fn command_usage(
+        &self,
+        ids: &'static [&'static str],
+        args: impl IntoIterator<Item = Self::Output>,
+        desc: &str,
+        long: bool,
+    ) -> Self::Output {
+        self.inner.command_usage(ids, args, desc, long)
+    }
+
+    fn deprecated_usage(
+        &self,
+        usage: Self::Output,
+        note: &'static str,
+        long: bool,
+    ) -> Self::Output {
+        self.inner.deprecated_usage(usage, note, long)
+    }
+
+    fn argument_details(&self, desc: &'static ArgumentDesc, text: &str) -> Self::Output {
+        self.inner.argument_details(desc, text)
+    }
+
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> Self::Output {
+        self.inner.argument_desc(name, is_required, desc)
+    }
+
+    fn flag_desc(
+        &self,
+        name: &'static str,
+        short: Option<&'static str>,
+        long: Option<&'static str>,
+        value: Option<&'static str>,
+        desc: &str,
+    ) -> Self::Output {
+        self.inner.flag_desc(name, short, long, value, desc)
+    }
+
+    fn command_desc(
+        &self,
+        summary: Option<&str>,
+        args: impl IntoIterator<Item = Self::Output>,
+        examples: Option<&str>,
+    ) -> Self::Output {
+        self.inner.command_desc(summary, args, examples)
+    }
+}
+
+impl<F: FoldError<Output = Result<String, fmt::Error>>, L: Localize> FoldError
+    for LocalizedFold<F, L>
+{
+    type Output = Result<String, fmt::Error>;
+
+    fn unterminated_quote(&self, quote: char, start: usize) -> Self::Output {
+        let quote_str = quote.to_string();
+
+        self.message("docbot-unterminated-quote", None, &[("quote", &quote_str)])
+            .map_or_else(|| self.inner.unterminated_quote(quote, start), Ok)
+    }
+
+    fn dangling_escape(&self, pos: usize) -> Self::Output {
+        self.message("docbot-dangling-escape", None, &[])
+            .map_or_else(|| self.inner.dangling_escape(pos), Ok)
+    }
+
+    fn no_id_match(&self, given: String, available: &'static [&'static str]) -> Self::Output {
+        let Some(mut s) = self.message("docbot-no-id-match", None, &[("given", &given)]) else {
+            return self.inner.no_id_match(given, available);
+        };
+
+        let dym = self.suggest(&given, available, &[]);
+
+        let hint = if !dym.is_empty() {
+            let options = Self::options(dym)?;
+
+            self.message("docbot-no-id-match", Some("did-you-mean"), &[(
+                "options", &options,
+            )])
+        } else if available.is_empty() {
+            None
+        } else {
+            let options = Self::options(available)?;
+
+            self.message("docbot-no-id-match", Some("available"), &[(
+                "options", &options,
+            )])
+        };
+
+        if let Some(hint) = hint {
+            s.push_str("  ");
+            s.push_str(&hint);
+        }
+
+        Ok(s)
+    }
+
+    fn ambiguous_id(&self, possible: &'static [&'static str], given: String) -> Self::Output {
+        let options = Self::options(possible)?;
+
+        self.message("docbot-ambiguous-id", None, &[
+            ("given", &given),
+            ("options", &options),
+        ])
+        .map_or_else(|| self.inner.ambiguous_id(possible, given), Ok)
+    }
+
+    fn incomplete_path(&self, possible: &'static [&'static str]) -> Self::Output {
+        let options = Self::options(possible)?;
+
+        self.message("docbot-incomplete-path", None, &[("options", &options)])
+            .map_or_else(|| self.inner.incomplete_path(possible), Ok)
+    }
+
+    fn trailing_path(&self, extra: String) -> Self::Output {
+        self.message("docbot-trailing-path", None, &[("extra", &extra)])
+            .map_or_else(|| self.inner.trailing_path(extra), Ok)
+    }
+
+    fn no_input(&self) -> Self::Output { self.inner.no_input() }
+
+    fn missing_required(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        self.message("docbot-missing-required", None, &[
+            ("cmd", cmd),
+            ("arg", arg),
+        ])
+        .map_or_else(|| self.inner.missing_required(cmd, arg), Ok)
+    }
+
+    fn missing_flag_value(&self, cmd: &'static str, arg: &'static str) -> Self::Output {
+        self.message("docbot-missing-flag-value", None, &[
+            ("cmd", cmd),
+            ("arg", arg),
+        ])
+        .map_or_else(|| self.inner.missing_flag_value(cmd, arg), Ok)
+    }
+
+    fn bad_convert(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+    ) -> Self::Output {
+        let error = inner?;
+
+        self.message("docbot-bad-convert", None, &[
+            ("cmd", cmd),
+            ("arg", arg),
+            ("error", &error),
+        ])
+        .map_or_else(|| self.inner.bad_convert(cmd, arg, Ok(error)), Ok)
+    }
+
+    fn bad_value(
+        &self,
+        cmd: &'static str,
+        arg: &'static str,
+        inner: Self::Output,
+        value: ArgumentValue,
+    ) -> Self::Output {
+        let error = inner?;
+
+        let Some(mut s) = self.message("docbot-bad-convert", None, &[
+            ("cmd", cmd),
+            ("arg", arg),
+            ("error", &error),
+        ]) else {
+            return self.inner.bad_value(cmd, arg, Ok(error), value);
+        };
+
+        let suggestions = self.suggest(&value.given, value.values, value.canonical);
+
+        if !suggestions.is_empty() {
+            let options = Self::options(suggestions)?;
+
+            if let Some(hint) = self.message("docbot-bad-convert", Some("did-you-mean"), &[(
+                "options", &options,
+            )]) {
+                s.push_str("  ");
+                s.push_str(&hint);
+            }
+        }
+
+        Ok(s)
+    }
+
+    fn trailing(&self, cmd: &'static str, extra: String) -> Self::Output {
+        self.message("docbot-trailing", None, &[("cmd", cmd), ("extra", &extra)])
+            .map_or_else(|| self.inner.trailing(cmd, extra), Ok)
+    }
+
+    fn subcommand(&self, subcmd: &'static str, inner: Self::Output) -> Self::Output {
+        let error = inner?;
+
+        self.message("docbot-subcommand", None, &[
+            ("subcmd", subcmd),
+            ("error", &error),
+        ])
+        .map_or_else(|| self.inner.subcommand(subcmd, Ok(error)), Ok)
+    }
+
+    fn usage(
+        &self,
+        parents: &[&'static str],
+        usage: &'static CommandUsage,
+        inner: Self::Output,
+    ) -> Self::Output {
+        let error = inner?;
+        let mut usage_str = String::new();
+
+        SimpleFoldError::write_usage(&mut usage_str, parents, usage)?;
+
+        self.message("docbot-usage", None, &[
+            ("usage", &usage_str),
+            ("error", &error),
+        ])
+        .map_or_else(|| self.inner.usage(parents, usage, Ok(error)), Ok)
+    }
+
+    fn command_path(&self, path: &[&'static str], inner: Self::Output) -> Self::Output {
+        if path.is_empty() {
+            return self.inner.command_path(path, inner);
+        }
+
+        let error = inner?;
+        let path_str = path.join(" ");
+
+        self.message("docbot-command-path", None, &[
+            ("path", &path_str),
+            ("error", &error),
+        ])
+        .map_or_else(|| self.inner.command_path(path, Ok(error)), Ok)
+    }
+
+    fn spanned(&self, span: Span, inner: Self::Output) -> Self::Output {
+        self.inner.spanned(span, inner)
+    }
+
+    fn other(&self, error: Anyhow) -> Self::Output { self.inner.other(error) }
+
+    fn suggest(
+        &self,
+        given: &str,
+        available: &'static [&'static str],
+        canonical: &'static [&'static str],
+    ) -> Vec<&'static str> {
+        self.inner.suggest(given, available, canonical)
+    }
+}
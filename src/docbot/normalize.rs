@@ -0,0 +1,51 @@
+use caseless::Caseless;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Unicode normalization applied to command IDs and user input before they are
+/// matched against each other
+///
+/// This is selected for a command set with `#[docbot(normalize)]`, which
+/// enables NFKC normalization and full Unicode case folding, or
+/// `#[docbot(strip_diacritics)]`, which additionally removes combining marks
+/// such as accents.  Without either attribute, IDs are matched after
+/// lowercasing with [`str::to_lowercase`].  Both attributes require the
+/// `unicode` feature.
+///
+/// Case folding is locale-independent, so the Turkish dotted and dotless I
+/// receive no special treatment beyond the default case fold: `İ` folds to `i`
+/// followed by a combining dot, and so only matches a plain `i` once
+/// diacritics are stripped, while `ı` never matches `i` or `I`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Normalization {
+    /// Whether to apply NFKC normalization and Unicode case folding
+    pub unicode: bool,
+    /// Whether to remove combining marks, such as accents
+    pub strip_diacritics: bool,
+}
+
+impl Normalization {
+    /// Normalize a string, folding its case if `fold_case` is true
+    ///
+    /// Full-width and other compatibility characters are replaced with their
+    /// canonical equivalents, so e.g. `ｈｅｌｐ` becomes `help`.
+    #[must_use]
+    pub fn apply(self, s: &str, fold_case: bool) -> String {
+        if !(self.unicode || self.strip_diacritics) {
+            return if fold_case {
+                s.to_lowercase()
+            } else {
+                s.to_owned()
+            };
+        }
+
+        let chars = s
+            .nfkd()
+            .filter(|&c| !(self.strip_diacritics && is_combining_mark(c)));
+
+        if fold_case {
+            chars.default_case_fold().nfkc().collect()
+        } else {
+            chars.nfkc().collect()
+        }
+    }
+}
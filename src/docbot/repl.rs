@@ -0,0 +1,230 @@
+//! A simple line-based driver for [`Command`] types
+
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, BufRead, Write},
+    marker::PhantomData,
+};
+
+use super::{
+    tokenize_str, Anyhow, CaretFoldError, Command, CommandPath, FoldError, FoldHelp, Help,
+    SimpleFoldError, SimpleFoldHelp, SuggestingFoldError,
+};
+
+/// Reads commands line-by-line, parses them as `C`, and passes them to a
+/// handler function
+///
+/// Input is tokenized with [`tokenize_str`], and any errors are formatted using
+/// the provided [`FoldError`] implementation.  By default, tokenizer and parse
+/// errors are annotated with the input line and a caret underline using
+/// [`CaretFoldError`]; this can be disabled with
+/// [`with_carets`](Self::with_carets).  Unless disabled with
+/// [`with_help_command`](Self::with_help_command), lines beginning with `help`
+/// are intercepted and answered using [`Help::help`] and the provided
+/// [`FoldHelp`] implementation.  Running a command marked deprecated prints a
+/// warning before the handler's response.
+pub struct Repl<C, H, E = SuggestingFoldError, F = SimpleFoldHelp> {
+    handler: H,
+    fold_error: E,
+    fold_help: F,
+    prompt: Cow<'static, str>,
+    help_command: Option<&'static str>,
+    carets: bool,
+    _cmd: PhantomData<fn(C)>,
+}
+
+impl<C, H, E: fmt::Debug, F: fmt::Debug> fmt::Debug for Repl<C, H, E, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Repl")
+            .field("fold_error", &self.fold_error)
+            .field("fold_help", &self.fold_help)
+            .field("prompt", &self.prompt)
+            .field("help_command", &self.help_command)
+            .field("carets", &self.carets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C, H> Repl<C, H>
+where
+    C: Command + Help,
+    H: FnMut(C) -> Result<Option<String>, Anyhow>,
+{
+    /// Construct a new REPL calling `handler` for each parsed command
+    ///
+    /// The handler may return a string to print in response to the command.
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            fold_error: SimpleFoldError.with_help_command(Some("help")),
+            fold_help: SimpleFoldHelp,
+            prompt: "> ".into(),
+            help_command: Some("help"),
+            carets: true,
+            _cmd: PhantomData,
+        }
+    }
+}
+
+impl<C, H, E, F> Repl<C, H, E, F>
+where
+    C: Command + Help,
+    H: FnMut(C) -> Result<Option<String>, Anyhow>,
+    E: FoldError<Output = Result<String, fmt::Error>>,
+    F: FoldHelp<Output = Result<String, fmt::Error>>,
+{
+    /// Replace the [`FoldError`] implementation used to format errors
+    pub fn with_fold_error<T>(self, fold_error: T) -> Repl<C, H, T, F>
+    where
+        T: FoldError<Output = Result<String, fmt::Error>>,
+    {
+        let Self {
+            handler,
+            fold_help,
+            prompt,
+            help_command,
+            carets,
+            ..
+        } = self;
+
+        Repl {
+            handler,
+            fold_error,
+            fold_help,
+            prompt,
+            help_command,
+            carets,
+            _cmd: PhantomData,
+        }
+    }
+
+    /// Replace the [`FoldHelp`] implementation used to format help topics
+    pub fn with_fold_help<T>(self, fold_help: T) -> Repl<C, H, E, T>
+    where
+        T: FoldHelp<Output = Result<String, fmt::Error>>,
+    {
+        let Self {
+            handler,
+            fold_error,
+            prompt,
+            help_command,
+            carets,
+            ..
+        } = self;
+
+        Repl {
+            handler,
+            fold_error,
+            fold_help,
+            prompt,
+            help_command,
+            carets,
+            _cmd: PhantomData,
+        }
+    }
+
+    /// Set the prompt written before reading each line
+    #[must_use]
+    pub fn with_prompt(mut self, prompt: impl Into<Cow<'static, str>>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Set the name of the built-in help command, or disable it with `None`
+    ///
+    /// The help command referred to in parse errors is configured separately
+    /// by the [`FoldError`] implementation, e.g. with
+    /// [`SimpleFoldError::with_help_command`].
+    #[must_use]
+    pub fn with_help_command(mut self, help_command: Option<&'static str>) -> Self {
+        self.help_command = help_command;
+        self
+    }
+
+    /// Enable or disable annotating errors with a caret underline
+    #[must_use]
+    pub fn with_carets(mut self, carets: bool) -> Self {
+        self.carets = carets;
+        self
+    }
+
+    fn help(&self, path: &[String]) -> Result<String, fmt::Error> {
+        match C::Path::parse_opt(path) {
+            Ok(path) => self.fold_help.fold_topic(C::help(path)),
+            Err(e) => self.fold_error.fold_path_parse(e),
+        }
+    }
+
+    /// Process a single line of input, returning the response to display, if
+    /// any
+    ///
+    /// # Errors
+    /// This function fails if the error or help formatter fails.
+    pub fn eval(&mut self, line: &str) -> Result<Option<String>, fmt::Error> {
+        let carets = CaretFoldError::new(line, &self.fold_error);
+        let fold_error: &dyn FoldError<Output = _> = if self.carets {
+            &carets
+        } else {
+            &self.fold_error
+        };
+
+        let tokens = match tokenize_str(line).spanned().collect::<Result<Vec<_>, _>>() {
+            Ok(t) => t,
+            Err(e) => return fold_error.fold_tokenize(e).map(Some),
+        };
+
+        match tokens.split_first() {
+            None => Ok(None),
+            Some((head, rest)) if Some(head.value.as_str()) == self.help_command => {
+                let path: Vec<_> = rest.iter().map(|t| t.value.clone()).collect();
+
+                self.help(&path).map(Some)
+            },
+            Some((head, _)) => match C::parse_spanned(&tokens) {
+                Ok(cmd) => {
+                    let warning = cmd
+                        .deprecated()
+                        .map(|d| format!("warning: `{}` is deprecated: {}", head.value, d));
+
+                    let resp = match (self.handler)(cmd) {
+                        Ok(s) => s,
+                        Err(e) => Some(self.fold_error.fold_anyhow(e)?),
+                    };
+
+                    Ok(match (warning, resp) {
+                        (Some(w), Some(r)) => Some(format!("{w}\n{r}")),
+                        (w, r) => w.or(r),
+                    })
+                },
+                Err(e) => fold_error
+                    .fold_flat_parse(e.flatten().with_usage::<C>())
+                    .map(Some),
+            },
+        }
+    }
+
+    /// Read and evaluate lines from `input` until it is exhausted, writing the
+    /// prompt and any responses to `output`
+    ///
+    /// # Errors
+    /// This function fails if reading from `input` or writing to `output`
+    /// fails, or if the error or help formatter fails.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        let mut line = String::new();
+
+        loop {
+            output.write_all(self.prompt.as_bytes())?;
+            output.flush()?;
+
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                break Ok(());
+            }
+
+            if let Some(resp) = self.eval(&line).map_err(io::Error::other)? {
+                writeln!(output, "{resp}")?;
+            }
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use std::cmp::Ordering;
+
+/// A source of "did you mean" suggestions for input that didn't match any
+/// command ID
+pub trait Suggester {
+    /// Select the options most likely to have been meant by `given`, ordered
+    /// from most to least likely
+    ///
+    /// `canonical` holds the canonical name (i.e. [`to_str`] value) of the
+    /// command each of `options` belongs to, in the same order, so that a
+    /// command can be suggested once by its canonical name rather than once
+    /// for each of its aliases.
+    ///
+    /// [`to_str`]: crate::CommandId::to_str
+    fn suggest(
+        &self,
+        given: &str,
+        options: &[&'static str],
+        canonical: &[&'static str],
+    ) -> Vec<&'static str>;
+}
+
+impl<S: Suggester + ?Sized> Suggester for &S {
+    fn suggest(
+        &self,
+        given: &str,
+        options: &[&'static str],
+        canonical: &[&'static str],
+    ) -> Vec<&'static str> {
+        (**self).suggest(given, options, canonical)
+    }
+}
+
+/// A [`Suggester`] that never suggests anything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoSuggestions;
+
+impl Suggester for NoSuggestions {
+    fn suggest(&self, _: &str, _: &[&'static str], _: &[&'static str]) -> Vec<&'static str> {
+        vec![]
+    }
+}
+
+/// A string similarity metric used by [`MetricSuggester`]
+///
+/// This trait is implemented for closures taking the input and an option and
+/// returning their similarity.
+pub trait Metric {
+    /// Score the similarity of `given` to `option`, from 0.0 for completely
+    /// different strings to 1.0 for identical strings
+    fn similarity(&self, given: &str, option: &str) -> f64;
+}
+
+impl<F: Fn(&str, &str) -> f64> Metric for F {
+    fn similarity(&self, given: &str, option: &str) -> f64 { self(given, option) }
+}
+
+/// Normalized Damerau-Levenshtein similarity
+#[cfg(feature = "strsim")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DamerauLevenshtein;
+
+#[cfg(feature = "strsim")]
+impl Metric for DamerauLevenshtein {
+    fn similarity(&self, given: &str, option: &str) -> f64 {
+        strsim::normalized_damerau_levenshtein(given, option)
+    }
+}
+
+/// Jaro-Winkler similarity, which favors strings sharing a common prefix
+///
+/// Scores under this metric tend to be higher than under others, so it is
+/// best paired with a higher [`threshold`](MetricSuggester::threshold).
+#[cfg(feature = "strsim")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JaroWinkler;
+
+#[cfg(feature = "strsim")]
+impl Metric for JaroWinkler {
+    fn similarity(&self, given: &str, option: &str) -> f64 { strsim::jaro_winkler(given, option) }
+}
+
+/// Damerau-Levenshtein similarity where substituting a key adjacent on a QWERTY
+/// keyboard counts as only half an edit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyboardDistance;
+
+impl KeyboardDistance {
+    const ROWS: [(&'static str, f64); 4] = [
+        ("1234567890-=", 0.0),
+        ("qwertyuiop[]", 0.5),
+        ("asdfghjkl;'", 0.75),
+        ("zxcvbnm,./", 1.25),
+    ];
+
+    fn position(c: char) -> Option<(f64, f64)> {
+        let c = c.to_ascii_lowercase();
+
+        Self::ROWS
+            .iter()
+            .zip(0_u8..)
+            .find_map(|((keys, offset), row)| {
+                keys.chars()
+                    .zip(0_u8..)
+                    .find(|(k, _)| *k == c)
+                    .map(|(_, col)| (f64::from(col) + offset, f64::from(row)))
+            })
+    }
+
+    fn substitution_cost(a: char, b: char) -> f64 {
+        if a.to_lowercase().eq(b.to_lowercase()) {
+            return 0.0;
+        }
+
+        let adjacent = match (Self::position(a), Self::position(b)) {
+            (Some((ax, ay)), Some((bx, by))) => (ax - bx).abs() <= 1.0 && (ay - by).abs() <= 1.0,
+            _ => false,
+        };
+
+        if adjacent {
+            0.5
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Metric for KeyboardDistance {
+    #[allow(clippy::cast_precision_loss)]
+    fn similarity(&self, given: &str, option: &str) -> f64 {
+        let a: Vec<_> = given.chars().collect();
+        let b: Vec<_> = option.chars().collect();
+        let len = a.len().max(b.len());
+
+        if len == 0 {
+            return 1.0;
+        }
+
+        // Optimal string alignment distance, keeping only the last three rows
+        // of the table, with the current row last
+        let mut rows = vec![vec![0.0; b.len() + 1]; 3];
+
+        for (j, d) in rows[2].iter_mut().enumerate() {
+            *d = j as f64;
+        }
+
+        for i in 1..=a.len() {
+            rows.rotate_left(1);
+            rows[2][0] = i as f64;
+
+            for j in 1..=b.len() {
+                let mut d = (rows[1][j] + 1.0)
+                    .min(rows[2][j - 1] + 1.0)
+                    .min(rows[1][j - 1] + Self::substitution_cost(a[i - 1], b[j - 1]));
+
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    d = d.min(rows[0][j - 2] + 1.0);
+                }
+
+                rows[2][j] = d;
+            }
+        }
+
+        1.0 - rows[2][b.len()] / len as f64
+    }
+}
+
+/// Phonetic similarity, comparing the American Soundex codes of each string
+///
+/// Only ASCII letters are considered, so strings without any have no
+/// similarity to anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Phonetic;
+
+impl Phonetic {
+    fn soundex(s: &str) -> Option<[u8; 4]> {
+        fn digit(c: u8) -> Option<u8> {
+            Some(match c {
+                b'b' | b'f' | b'p' | b'v' => b'1',
+                b'c' | b'g' | b'j' | b'k' | b'q' | b's' | b'x' | b'z' => b'2',
+                b'd' | b't' => b'3',
+                b'l' => b'4',
+                b'm' | b'n' => b'5',
+                b'r' => b'6',
+                _ => return None,
+            })
+        }
+
+        let mut letters = s
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_lowercase());
+        let first = letters.next()?;
+        let mut code = [first.to_ascii_uppercase(), b'0', b'0', b'0'];
+        let mut last = digit(first);
+        let mut len = 1;
+
+        for c in letters {
+            if len == code.len() {
+                break;
+            }
+
+            match digit(c) {
+                Some(d) if last != Some(d) => {
+                    code[len] = d;
+                    len += 1;
+                    last = Some(d);
+                },
+                Some(_) => (),
+                // H and W don't separate letters with the same code, but
+                // vowels do
+                None if c == b'h' || c == b'w' => (),
+                None => last = None,
+            }
+        }
+
+        Some(code)
+    }
+}
+
+impl Metric for Phonetic {
+    #[allow(clippy::cast_precision_loss)]
+    fn similarity(&self, given: &str, option: &str) -> f64 {
+        match (Self::soundex(given), Self::soundex(option)) {
+            (Some(a), Some(b)) => {
+                let same = a.iter().zip(&b).filter(|(a, b)| a == b).count();
+
+                same as f64 / a.len() as f64
+            },
+            _ => 0.0,
+        }
+    }
+}
+
+/// A [`Suggester`] ranking options by a [`Metric`]
+///
+/// Options scoring below a threshold are discarded, and a command with several
+/// matching aliases is only suggested once, by its canonical name.  By
+/// default, options are truncated to one character longer than the input
+/// before scoring, since commands can be given by a prefix of their ID.
+#[derive(Debug, Clone, Copy)]
+pub struct MetricSuggester<M> {
+    metric: M,
+    threshold: f64,
+    max: Option<usize>,
+    truncate: bool,
+}
+
+impl<M: Default> Default for MetricSuggester<M> {
+    fn default() -> Self { Self::new(M::default()) }
+}
+
+impl<M> MetricSuggester<M> {
+    /// Construct a new suggester using `metric`, with a threshold of 0.3 and
+    /// no limit on the number of suggestions
+    pub fn new(metric: M) -> Self {
+        Self {
+            metric,
+            threshold: 0.3,
+            max: None,
+            truncate: true,
+        }
+    }
+
+    /// Set the minimum similarity score an option needs to be suggested
+    #[must_use]
+    pub fn threshold(self, threshold: f64) -> Self { Self { threshold, ..self } }
+
+    /// Set the maximum number of suggestions to return
+    #[must_use]
+    pub fn max(self, max: usize) -> Self {
+        Self {
+            max: Some(max),
+            ..self
+        }
+    }
+
+    /// Set whether options are truncated to the length of the input before
+    /// scoring
+    #[must_use]
+    pub fn truncate(self, truncate: bool) -> Self { Self { truncate, ..self } }
+}
+
+impl<M: Metric> MetricSuggester<M> {
+    /// Score the similarity of `given` to `option`, or return `None` if it
+    /// falls below the threshold
+    pub(crate) fn score(&self, given: &str, option: &str) -> Option<f64> {
+        let option = match option.char_indices().nth(given.chars().count() + 1) {
+            Some((end, _)) if self.truncate => &option[..end],
+            _ => option,
+        };
+        let score = self.metric.similarity(given, option);
+
+        (score >= self.threshold).then_some(score)
+    }
+}
+
+/// Sort scored options from most to least similar, breaking ties in favor of
+/// the option that sorts first
+pub(crate) fn sort_scores<S: Ord>(scores: &mut [(f64, S)]) {
+    scores.sort_by(|(a, m), (b, n)| b.partial_cmp(a).unwrap_or(Ordering::Equal).then(m.cmp(n)));
+}
+
+impl<M: Metric> Suggester for MetricSuggester<M> {
+    fn suggest(
+        &self,
+        given: &str,
+        options: &[&'static str],
+        canonical: &[&'static str],
+    ) -> Vec<&'static str> {
+        let mut scores: Vec<(f64, &'static str)> = vec![];
+
+        for (i, option) in options.iter().enumerate() {
+            let name = canonical.get(i).copied().unwrap_or(option);
+            let Some(score) = self.score(given, option) else {
+                continue;
+            };
+
+            match scores.iter_mut().find(|(_, n)| *n == name) {
+                Some((best, _)) => *best = best.max(score),
+                None => scores.push((score, name)),
+            }
+        }
+
+        sort_scores(&mut scores);
+
+        scores
+            .into_iter()
+            .take(self.max.unwrap_or(usize::MAX))
+            .map(|(_, n)| n)
+            .collect()
+    }
+}
+
+/// The [`Suggester`] used by [`SimpleFoldError`](crate::SimpleFoldError), and
+/// by [`SuggestingFoldError`](crate::SuggestingFoldError) by default
+///
+/// This ranks options by [`DamerauLevenshtein`] similarity if the
+/// `did-you-mean` feature is enabled, and makes no suggestions otherwise.
+#[cfg(feature = "strsim")]
+pub type DefaultSuggester = MetricSuggester<DamerauLevenshtein>;
+
+/// The [`Suggester`] used by [`SimpleFoldError`](crate::SimpleFoldError), and
+/// by [`SuggestingFoldError`](crate::SuggestingFoldError) by default
+///
+/// This ranks options by `DamerauLevenshtein` similarity if the
+/// `did-you-mean` feature is enabled, and makes no suggestions otherwise.
+#[cfg(not(feature = "strsim"))]
+pub type DefaultSuggester = NoSuggestions;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_similarity(metric: &impl Metric, given: &str, option: &str, expected: f64) {
+        let actual = metric.similarity(given, option);
+
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "similarity of {given:?} to {option:?} was {actual}, expected {expected}"
+        );
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn damerau_levenshtein() {
+        assert_similarity(&DamerauLevenshtein, "help", "help", 1.0);
+        assert_similarity(&DamerauLevenshtein, "ab", "ba", 0.5);
+        assert_similarity(&DamerauLevenshtein, "pnig", "ping", 0.75);
+        assert_similarity(&DamerauLevenshtein, "kitten", "sitting", 1.0 - 3.0 / 7.0);
+        assert_similarity(&DamerauLevenshtein, "", "", 1.0);
+        assert_similarity(&DamerauLevenshtein, "", "abc", 0.0);
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn jaro_winkler() {
+        assert_similarity(&JaroWinkler, "martha", "marhta", 0.9611);
+        assert_similarity(&JaroWinkler, "dixon", "dicksonx", 0.8133);
+        assert_similarity(&JaroWinkler, "abc", "xyz", 0.0);
+        assert_similarity(&JaroWinkler, "", "", 1.0);
+        assert_similarity(&JaroWinkler, "", "abc", 0.0);
+    }
+
+    #[test]
+    fn keyboard_distance() {
+        assert_similarity(&KeyboardDistance, "help", "help", 1.0);
+        assert_similarity(&KeyboardDistance, "HELP", "help", 1.0);
+        assert_similarity(&KeyboardDistance, "hwlp", "help", 0.875);
+        assert_similarity(&KeyboardDistance, "hxlp", "help", 0.75);
+        assert_similarity(&KeyboardDistance, "ab", "ba", 0.5);
+        assert_similarity(&KeyboardDistance, "pnig", "ping", 0.75);
+        assert_similarity(&KeyboardDistance, "", "", 1.0);
+        assert_similarity(&KeyboardDistance, "", "abc", 0.0);
+    }
+
+    #[test]
+    fn soundex() {
+        for (s, code) in [
+            ("Robert", "R163"),
+            ("Rupert", "R163"),
+            ("Ashcraft", "A261"),
+            ("Tymczak", "T522"),
+            ("Pfister", "P236"),
+            ("Lee", "L000"),
+        ] {
+            assert_eq!(
+                Phonetic::soundex(s),
+                Some(code.as_bytes().try_into().unwrap())
+            );
+        }
+
+        assert_eq!(Phonetic::soundex(""), None);
+        assert_eq!(Phonetic::soundex("123"), None);
+    }
+
+    #[test]
+    fn phonetic() {
+        assert_similarity(&Phonetic, "Robert", "Rupert", 1.0);
+        assert_similarity(&Phonetic, "Robert", "Rubin", 0.5);
+        assert_similarity(&Phonetic, "", "", 0.0);
+        assert_similarity(&Phonetic, "123", "123", 0.0);
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn default_suggester() {
+        const OPTIONS: &[&str] = &["ping", "pong", "help"];
+
+        let suggester = DefaultSuggester::default();
+
+        assert_eq!(suggester.suggest("pnig", OPTIONS, OPTIONS), [
+            "ping", "pong"
+        ]);
+        assert_eq!(suggester.suggest("pang", OPTIONS, OPTIONS), [
+            "ping", "pong"
+        ]);
+        assert_eq!(suggester.suggest("pang", &["pong", "ping"], &[]), [
+            "ping", "pong"
+        ]);
+        assert!(suggester.suggest("xyz", OPTIONS, OPTIONS).is_empty());
+        assert_eq!(
+            suggester.threshold(0.7).suggest("pnig", OPTIONS, OPTIONS),
+            ["ping"]
+        );
+        assert_eq!(suggester.max(1).suggest("pang", OPTIONS, OPTIONS), ["ping"]);
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn did_you_mean() {
+        let ranked: Vec<_> = crate::did_you_mean("pnig", ["help", "pong", "ping"]).collect();
+
+        assert_eq!(ranked, ["ping", "pong"]);
+        assert_eq!(crate::did_you_mean("xyz", ["help"]).count(), 0);
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn aliases_and_prefixes() {
+        let suggester = DefaultSuggester::default();
+
+        assert_eq!(
+            suggester.suggest("lst", &["ls", "list"], &["list", "list"]),
+            ["list"]
+        );
+        assert_eq!(suggester.suggest("hel", &["helpme"], &[]), ["helpme"]);
+        assert!(suggester
+            .truncate(false)
+            .suggest("hel", &["helpmeplease"], &[])
+            .is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use docbot::{prelude::*, Completion};
+
+/// Commands to complete
+#[derive(Docbot)]
+pub enum Cmd {
+    /// `ping [-v|--verbose] [-n|--count <n>] <host> [extra...]`: Ping a host
+    ///
+    /// # Arguments
+    /// verbose: Print more output
+    /// count: How many times to ping
+    /// host: The host to ping
+    /// extra: Extra options
+    Ping(bool, Option<u32>, String, Vec<String>),
+    /// `(pong|pung)`: Reply to a ping
+    Pong,
+    /// `admin <command...>`: Run an administrative command
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    Admin(Admin),
+    /// `help [-a|--all] [command...]`: Show help for a command
+    ///
+    /// # Arguments
+    /// all: Include hidden commands
+    /// command: The command to look up
+    Help {
+        all: bool,
+        #[docbot(path)]
+        command: Option<CmdPath>,
+    },
+}
+
+/// Administrative commands
+#[derive(Docbot, Debug)]
+pub enum Admin {
+    /// `ban <user>`: Ban a user
+    ///
+    /// # Arguments
+    /// user: The user to ban
+    Ban(String),
+    /// `banish <user>`: Banish a user
+    ///
+    /// # Arguments
+    /// user: The user to banish
+    #[docbot(allow_prefix)]
+    Banish(String),
+}
+
+fn show(completions: Vec<Completion>) -> Vec<String> {
+    completions
+        .into_iter()
+        .map(|c| match c {
+            Completion::Id(i) => i.into(),
+            Completion::Flag(f) => f,
+            Completion::Argument(a) => format!("<{}>", a.name),
+        })
+        .collect()
+}
+
+#[test]
+fn id_prefix() {
+    assert_eq!(show(Cmd::complete::<_, &str>([])), [
+        "ping", "pong", "pung", "admin", "help"
+    ]);
+    assert_eq!(show(Cmd::complete(["p"])), ["ping", "pong", "pung"]);
+    assert_eq!(show(Cmd::complete(["PU"])), ["pung"]);
+    assert!(Cmd::complete(["x"]).is_empty());
+}
+
+#[test]
+fn arguments() {
+    assert_eq!(show(Cmd::complete(["ping", ""])), ["<host>"]);
+    assert_eq!(show(Cmd::complete(["ping", "--c"])), ["--count", "<host>"]);
+    assert_eq!(show(Cmd::complete(["ping", "-n", ""])), ["<count>"]);
+    assert_eq!(show(Cmd::complete(["ping", "-n", "3", "h", ""])), [
+        "<extra>"
+    ]);
+    assert_eq!(show(Cmd::complete(["ping", "h", "x", "y", ""])), [
+        "<extra>"
+    ]);
+}
+
+#[test]
+fn subcommands() {
+    assert_eq!(show(Cmd::complete(["admin", ""])), ["ban", "banish"]);
+    assert_eq!(show(Cmd::complete(["admin", "bani"])), ["banish"]);
+    assert_eq!(show(Cmd::complete(["admin", "ban", ""])), ["<user>"]);
+}
+
+#[test]
+fn past_end() {
+    assert!(Cmd::complete(["pong", ""]).is_empty());
+    assert!(Cmd::complete(["admin", "ban", "bob", ""]).is_empty());
+    assert!(Cmd::complete(["nope", ""]).is_empty());
+}
+
+#[test]
+fn path_boundary() {
+    assert_eq!(show(Cmd::complete(["help", "-"])), [
+        "-a",
+        "--all",
+        "<command>"
+    ]);
+    assert_eq!(show(Cmd::complete(["help", "-a", "admin", "-"])), [
+        "<command>"
+    ]);
+    assert_eq!(show(Cmd::complete(["help", "admin", "ban", "--a"])), [
+        "<command>"
+    ]);
+}
@@ -0,0 +1,76 @@
+use docbot::{prelude::*, SimpleFoldHelp};
+
+/// Commands with default argument values
+#[derive(Docbot, Debug, PartialEq, Eq)]
+pub enum Cmd {
+    /// `ping [-n|--count <n>] <host> [port] [name]`: Ping a host
+    ///
+    /// # Arguments
+    /// count: How many times to ping
+    /// host: The host to ping
+    /// port: The port to ping
+    /// name: A name for the ping
+    Ping {
+        #[docbot(default = "3")]
+        count: u32,
+        host: String,
+        #[docbot(default = "80")]
+        port: u16,
+        #[docbot(default)]
+        name: String,
+    },
+    /// `wait [duration]`: Wait for a while
+    ///
+    /// # Arguments
+    /// duration: How many seconds to wait
+    Wait(#[docbot(default = "10", parse_with = parse_seconds)] u64),
+}
+
+fn parse_seconds(s: &str) -> Result<u64, std::num::ParseIntError> {
+    s.trim_end_matches('s').parse()
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(Cmd::parse(["ping", "h"]).unwrap(), Cmd::Ping {
+        count: 3,
+        host: "h".into(),
+        port: 80,
+        name: String::new(),
+    });
+    assert_eq!(Cmd::parse(["wait"]).unwrap(), Cmd::Wait(10));
+}
+
+#[test]
+fn overridden() {
+    assert_eq!(
+        Cmd::parse(["ping", "-n", "5", "h", "8", "x"]).unwrap(),
+        Cmd::Ping {
+            count: 5,
+            host: "h".into(),
+            port: 8,
+            name: "x".into(),
+        }
+    );
+    assert_eq!(Cmd::parse(["wait", "3s"]).unwrap(), Cmd::Wait(3));
+}
+
+#[test]
+fn help() {
+    let help = SimpleFoldHelp
+        .fold_topic(Cmd::help(Some(CmdId::Ping)))
+        .unwrap();
+
+    assert!(
+        help.contains("-n, --count <n> (default: 3): How many"),
+        "{help}"
+    );
+    assert!(help.contains("port (default: 80): The port"), "{help}");
+    assert!(help.contains("name (optional): A name"), "{help}");
+
+    let help = SimpleFoldHelp
+        .fold_topic(Cmd::help(Some(CmdId::Wait)))
+        .unwrap();
+
+    assert!(help.contains("duration (default: 10): How many"), "{help}");
+}
@@ -0,0 +1,157 @@
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use docbot::{prelude::*, Dispatch, SendAsyncDispatch};
+
+/// Commands with argument names that aren't valid identifiers
+#[derive(Docbot, Debug)]
+#[docbot(dispatch)]
+pub enum Cmd {
+    /// `set <type> <value>`: Set a value
+    ///
+    /// # Arguments
+    /// type: The type of the value
+    /// value: The value
+    Set(String, String),
+    /// `rename <the type> <newName>`: Rename a type
+    ///
+    /// # Arguments
+    /// the type: The type to rename
+    /// newName: Its new name
+    Rename(String, String),
+    /// `pick <1st> [2nd]`: Pick a value
+    ///
+    /// # Arguments
+    /// 1st: The first choice
+    /// 2nd: The second choice
+    Pick(String, Option<String>),
+    /// `use <ctx> <self>`: Use a context
+    ///
+    /// # Arguments
+    /// ctx: The context
+    /// self: Whose context
+    Use(String, String),
+    /// `ban [--days <n>] <user>`: Ban a user
+    ///
+    /// # Arguments
+    /// days: How long to ban them for
+    /// user: The user to ban
+    Ban { user: String, days: Option<u32> },
+}
+
+struct Handler;
+
+impl CmdHandler<&'static str> for Handler {
+    type Output = String;
+
+    fn set(&mut self, ctx: &str, ty: String, value: String) -> String {
+        format!("{ctx}: set {ty} = {value}")
+    }
+
+    fn rename(&mut self, ctx: &str, ty: String, new_name: String) -> String {
+        format!("{ctx}: rename {ty} to {new_name}")
+    }
+
+    fn pick(&mut self, ctx: &str, first: String, second: Option<String>) -> String {
+        format!("{ctx}: pick {first} {second:?}")
+    }
+
+    fn r#use(&mut self, ctx: &str, inner: String, owner: String) -> String {
+        format!("{ctx}: use {inner} of {owner}")
+    }
+
+    fn ban(&mut self, ctx: &str, user: String, days: Option<u32>) -> String {
+        format!("{ctx}: ban {user} for {days:?}")
+    }
+}
+
+fn dispatch(args: &[&str]) -> String { Cmd::parse(args).unwrap().dispatch(&mut Handler, "test") }
+
+#[test]
+fn keyword_names() {
+    assert_eq!(dispatch(&["set", "color", "red"]), "test: set color = red");
+    assert_eq!(dispatch(&["use", "a", "b"]), "test: use a of b");
+}
+
+#[test]
+fn invalid_names() {
+    assert_eq!(dispatch(&["rename", "a", "b"]), "test: rename a to b");
+    assert_eq!(dispatch(&["pick", "a"]), "test: pick a None");
+}
+
+#[test]
+fn declaration_order() {
+    assert_eq!(
+        dispatch(&["ban", "--days", "3", "bob"]),
+        "test: ban bob for Some(3)"
+    );
+    assert_eq!(dispatch(&["ban", "bob"]), "test: ban bob for None");
+}
+
+/// Commands dispatched on a multithreaded executor
+#[derive(Docbot, Debug)]
+#[docbot(async_dispatch(send))]
+pub enum Remote {
+    /// `ping <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// host: The host to ping
+    Ping(String),
+    /// `admin <command...>`: Run an administrative command
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    Admin(RemoteAdmin),
+}
+
+/// Administrative commands
+#[derive(Docbot, Debug)]
+#[docbot(async_dispatch(send))]
+pub enum RemoteAdmin {
+    /// `kick <user>`: Remove a user
+    ///
+    /// # Arguments
+    /// user: The user to remove
+    Kick(String),
+}
+
+struct AsyncHandler;
+
+impl RemoteAsyncHandler<u32> for AsyncHandler {
+    type Output = String;
+
+    async fn ping(&mut self, ctx: u32, host: String) -> String { format!("{ctx}: ping {host}") }
+}
+
+impl RemoteAdminAsyncHandler<u32> for AsyncHandler {
+    type Output = String;
+
+    async fn kick(&mut self, ctx: u32, user: String) -> String { format!("{ctx}: kick {user}") }
+}
+
+fn assert_send<T: Send>(value: T) -> T { value }
+
+fn dispatch_send(args: &[&str]) -> String {
+    let mut handler = AsyncHandler;
+    let fut = assert_send(Remote::parse(args).unwrap().dispatch_send(&mut handler, 7));
+
+    let out = match pin!(fut).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(s) => s,
+        Poll::Pending => panic!("handler future was not ready"),
+    };
+
+    out
+}
+
+#[test]
+fn send_futures() {
+    assert_eq!(
+        dispatch_send(&["ping", "example.com"]),
+        "7: ping example.com"
+    );
+    assert_eq!(dispatch_send(&["admin", "kick", "bob"]), "7: kick bob");
+}
@@ -0,0 +1,288 @@
+use docbot::{
+    prelude::*, ArgumentName, CommandParseError, CommandUsage, Help as _, HelpTopic,
+    SimpleFoldError, ValueHint,
+};
+
+/// Top-level commands
+#[derive(Docbot, Debug)]
+pub enum Cmd {
+    /// `ping <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// host: The host to ping
+    Ping(String),
+    /// `admin <command...>`: Run an administrative command
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    Admin(Admin),
+    /// `speed <speed>`: Set the ping speed
+    ///
+    /// # Arguments
+    /// speed: The new speed
+    Speed(Speed),
+    /// `level <level>`: Set the log level
+    ///
+    /// # Arguments
+    /// level: The new log level
+    Level(Level),
+}
+
+/// A log level, parsed without the help of `#[derive(Docbot)]`
+#[derive(Debug)]
+pub struct Level;
+
+/// Error returned for an unrecognized [`Level`]
+#[derive(Debug, thiserror::Error)]
+#[error("unknown level")]
+pub struct UnknownLevel;
+
+impl std::str::FromStr for Level {
+    type Err = UnknownLevel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Level::values()
+            .contains(&s)
+            .then_some(Self)
+            .ok_or(UnknownLevel)
+    }
+}
+
+impl ValueHint for Level {
+    fn values() -> &'static [&'static str] { &["debug", "info", "warn"] }
+}
+
+/// Ping speeds
+#[derive(Docbot, Debug, Clone, Copy)]
+pub enum Speed {
+    /// `fast`: Ping quickly
+    Fast,
+    /// `slow`: Ping slowly
+    Slow,
+}
+
+/// `help [command...]`: Show help for a command
+///
+/// # Arguments
+/// command: The command to look up
+#[derive(Docbot)]
+pub struct Help {
+    #[docbot(path)]
+    command: Option<CmdPath>,
+}
+
+/// Administrative commands
+#[derive(Docbot, Debug)]
+pub enum Admin {
+    /// `ban <user> [days]`: Ban a user
+    ///
+    /// # Arguments
+    /// user: The user to ban
+    /// days: How long to ban them for
+    Ban(String, Option<u32>),
+    /// `user <command...>`: Manage users
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    User(User),
+}
+
+/// User management commands
+#[derive(Docbot, Debug)]
+pub enum User {
+    /// `ban <user>`: Ban a user permanently
+    ///
+    /// # Arguments
+    /// user: The user to ban
+    Ban(String),
+}
+
+fn fold(args: &[&str]) -> String {
+    let err = Cmd::parse(args).unwrap_err();
+
+    SimpleFoldError
+        .with_help_command(Some("help"))
+        .fold_flat_parse(err.flatten().with_usage::<Cmd>())
+        .unwrap()
+}
+
+#[test]
+fn usage() {
+    assert_eq!(
+        fold(&["ping"]),
+        "Missing required argument 'host' to command 'ping'  See `help ping`.\n\
+         Usage: ping <host>"
+    );
+    assert_eq!(
+        fold(&["ping", "a", "b"]),
+        "Unexpected extra argument \"b\" to 'ping'  See `help ping`.\nUsage: ping <host>"
+    );
+}
+
+#[test]
+fn nested_usage() {
+    assert_eq!(
+        fold(&["admin", "ban"]),
+        "Missing required argument 'user' to command 'ban'  See `help admin ban`.\n\
+         Usage: admin ban <user> [days]"
+    );
+}
+
+#[test]
+fn flat_path() {
+    let err = Cmd::parse(["admin", "user", "ban"]).unwrap_err().flatten();
+
+    assert_eq!(err.path, ["admin", "user", "ban"]);
+    assert!(std::ptr::eq(
+        err.help_topic::<Cmd>(),
+        User::help(Some(UserId::Ban))
+    ));
+    assert!(matches!(
+        err.help_topic::<Cmd>(),
+        HelpTopic::Command(
+            CommandUsage {
+                desc: "Ban a user permanently",
+                ..
+            },
+            _
+        )
+    ));
+
+    let err = Cmd::parse(["pnig"]).unwrap_err().flatten();
+
+    assert!(err.path.is_empty());
+    assert!(std::ptr::eq(
+        err.help_topic::<Cmd>(),
+        Cmd::help(None::<CmdId>)
+    ));
+}
+
+#[test]
+fn did_you_mean() {
+    assert_eq!(
+        fold(&["speed", "fsat"]),
+        "Couldn't parse argument 'speed' of command 'speed': Not sure what you mean by \"fsat\".  \
+         Did you mean: 'fast'  See `help speed`."
+    );
+    assert_eq!(
+        fold(&["level", "wran"]),
+        "Couldn't parse argument 'level' of command 'level': unknown level  Did you mean: 'warn'  \
+         See `help level`."
+    );
+}
+
+#[test]
+fn path_value_hint() {
+    let Err(CommandParseError::BadConvert(_, _, Some(value))) = Help::parse(["help", "pnig"])
+    else {
+        panic!("expected a value hint");
+    };
+
+    assert_eq!(value.given, "pnig");
+    assert_eq!(value.canonical, ["ping", "admin", "speed", "level"]);
+    assert!(Help::parse(["help", "ping"]).unwrap().command.is_some());
+}
+
+#[test]
+fn plain_errors() {
+    assert!(matches!(
+        Cmd::parse(["ping"]),
+        Err(CommandParseError::MissingRequired(ArgumentName {
+            cmd: "ping",
+            arg: "host"
+        }))
+    ));
+    assert!(matches!(
+        Cmd::parse(["ping", "a", "b"]),
+        Err(CommandParseError::Trailing("ping", ref s)) if s == "b"
+    ));
+}
+
+#[test]
+fn no_usage_without_lookup() {
+    let err = Cmd::parse(["ping"]).unwrap_err().flatten();
+
+    assert!(err.usage.is_none());
+    assert_eq!(
+        SimpleFoldError.fold_flat_parse(err).unwrap(),
+        "Missing required argument 'host' to command 'ping'"
+    );
+}
+
+/// Implements only the hooks without default bodies
+struct Names;
+
+impl FoldError for Names {
+    type Output = String;
+
+    fn no_id_match(&self, given: String, _: &'static [&'static str]) -> String { given }
+
+    fn ambiguous_id(&self, _: &'static [&'static str], given: String) -> String { given }
+
+    fn incomplete_path(&self, _: &'static [&'static str]) -> String { "incomplete".into() }
+
+    fn trailing_path(&self, extra: String) -> String { extra }
+
+    fn no_input(&self) -> String { "no input".into() }
+
+    fn missing_required(&self, _: &'static str, arg: &'static str) -> String { arg.into() }
+
+    fn bad_convert(&self, _: &'static str, arg: &'static str, _: String) -> String { arg.into() }
+
+    fn trailing(&self, _: &'static str, extra: String) -> String { extra }
+
+    fn subcommand(&self, subcmd: &'static str, inner: String) -> String {
+        format!("{subcmd}: {inner}")
+    }
+
+    fn other(&self, error: anyhow::Error) -> String { format!("other: {error}") }
+}
+
+#[test]
+fn default_hooks() {
+    let err = docbot::tokenize_str("ping 'a")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+
+    assert_eq!(
+        Names.fold_tokenize(err),
+        "other: unterminated '\\'' quote starting at byte 5"
+    );
+    assert_eq!(
+        Names.fold_command_parse(Cmd::parse(["admin", "ban", "x", "y"]).unwrap_err()),
+        "admin: days"
+    );
+}
+
+/// A hand-written command implementing only `parse`
+struct Manual(String);
+
+impl Command for Manual {
+    type Id = AdminId;
+    type Path = AdminId;
+
+    fn parse<I: IntoIterator<Item = S>, S: AsRef<str>>(iter: I) -> Result<Self, CommandParseError> {
+        let mut iter = iter.into_iter();
+        let id = iter.next().ok_or(CommandParseError::NoInput)?;
+
+        Ok(Self(id.as_ref().into()))
+    }
+
+    fn id(&self) -> AdminId { AdminId::Ban }
+}
+
+#[test]
+fn manual_parse_spanned() {
+    let tokens: Vec<_> = docbot::tokenize_str("hi there")
+        .spanned()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(Manual::parse_spanned(&tokens).unwrap().0, "hi");
+    assert!(matches!(
+        Manual::parse_spanned(Vec::<docbot::Spanned<String>>::new()),
+        Err(CommandParseError::NoInput)
+    ));
+}
@@ -0,0 +1,90 @@
+use docbot::prelude::*;
+
+/// Commands taking flags
+#[derive(Docbot)]
+pub enum Cmd {
+    /// `echo [-n|--no-newline] [text...]`: Print some text
+    ///
+    /// # Arguments
+    /// no-newline: Don't print a trailing newline
+    /// text: The text to print
+    Echo { no_newline: bool, text: Vec<String> },
+    /// `help [--all] [command...]`: Show help for a command
+    ///
+    /// # Arguments
+    /// all: Include hidden commands
+    /// command: The command to look up
+    Help {
+        all: bool,
+        #[docbot(path)]
+        command: Option<CmdPath>,
+    },
+    /// `admin <command...>`: Run an administrative command
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    Admin(Admin),
+}
+
+/// Administrative commands
+#[derive(Docbot, Debug)]
+pub enum Admin {
+    /// `kick [-f|--force] <user>`: Remove a user
+    ///
+    /// # Arguments
+    /// force: Remove the user even if they're an admin
+    /// user: The user to remove
+    Kick { force: bool, user: String },
+}
+
+#[test]
+fn any_order() {
+    for args in [&["echo", "-n", "hi"][..], &["echo", "hi", "--no-newline"]] {
+        match Cmd::parse(args).unwrap() {
+            Cmd::Echo { no_newline, text } => {
+                assert!(no_newline);
+                assert_eq!(text, ["hi"]);
+            },
+            _ => panic!("unexpected command"),
+        }
+    }
+}
+
+#[test]
+fn end_of_options() {
+    match Cmd::parse(["echo", "--", "--no-newline", "-n"]).unwrap() {
+        Cmd::Echo { no_newline, text } => {
+            assert!(!no_newline);
+            assert_eq!(text, ["--no-newline", "-n"]);
+        },
+        _ => panic!("unexpected command"),
+    }
+}
+
+#[test]
+fn subcommand_flags() {
+    match Cmd::parse(["admin", "kick", "bob", "--force"]).unwrap() {
+        Cmd::Admin(Admin::Kick { force, user }) => {
+            assert!(force);
+            assert_eq!(user, "bob");
+        },
+        _ => panic!("unexpected command"),
+    }
+}
+
+#[test]
+fn path_boundary() {
+    match Cmd::parse(["help", "--all", "admin", "kick"]).unwrap() {
+        Cmd::Help { all, command } => {
+            assert!(all);
+            assert!(matches!(
+                command,
+                Some(CmdPath::Admin(Some(ref p))) if matches!(**p, AdminId::Kick)
+            ));
+        },
+        _ => panic!("unexpected command"),
+    }
+
+    assert!(Cmd::parse(["help", "admin", "--all"]).is_err());
+}
@@ -0,0 +1,197 @@
+use docbot::{prelude::*, MarkdownFoldHelp, PermissionFoldHelp, SimpleFoldHelp};
+
+/// Commands to document
+#[derive(Docbot)]
+pub enum Cmd {
+    /// `ping [-n|--count <n>] <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// count: How many times to ping
+    /// host: The host to ping, e.g. *example_host*
+    Ping(Option<u32>, String),
+    /// `pong`: Reply to a ping
+    #[docbot(deprecated = "use ping instead")]
+    Pong,
+}
+
+/// Moderation commands
+#[derive(Docbot)]
+pub enum Mod {
+    /// `warn <user>`: Warn a user
+    ///
+    /// # Arguments
+    /// user: The user to warn
+    Warn(String),
+    /// `kick <user>`: Kick a user
+    ///
+    /// # Arguments
+    /// user: The user to kick
+    ///
+    /// # Permissions
+    /// kick
+    Kick(String),
+    /// `ban <user>`: Ban a user
+    ///
+    /// # Arguments
+    /// user: The user to ban
+    ///
+    /// # Requires
+    /// kick, ban
+    /// audit
+    Ban(String),
+}
+
+/// `tune [-f|--fallback <mode>] [mode]`: Change how pings are sent
+///
+/// # Arguments
+/// fallback: The mode to use if the first fails
+/// mode: The new ping mode
+#[derive(Docbot)]
+pub struct Tune {
+    pub fallback: Option<Mode>,
+    #[docbot(default = "auto")]
+    pub mode: Mode,
+}
+
+/// Ping modes
+#[derive(Docbot, Clone, Copy)]
+pub enum Mode {
+    /// `fast`: Send pings quickly
+    Fast,
+    /// `(slow|gentle)`: Send pings slowly
+    Slow,
+    /// `auto`: Pick a mode automatically
+    Auto,
+}
+
+/// Implements only the hooks without default bodies
+struct Plain;
+
+impl FoldHelp for Plain {
+    type Output = String;
+
+    fn command_topic(&self, usage: String, desc: String) -> String { format!("{usage}\n{desc}") }
+
+    fn command_set_topic(
+        &self,
+        _: Option<&str>,
+        commands: impl IntoIterator<Item = String>,
+    ) -> String {
+        commands.into_iter().collect::<Vec<_>>().join("\n")
+    }
+
+    fn custom_topic(&self, topic: &'static str) -> String { topic.into() }
+
+    fn argument_usage(&self, name: &'static str, is_required: bool, _: bool) -> String {
+        if is_required {
+            format!("<{name}>")
+        } else {
+            format!("[{name}]")
+        }
+    }
+
+    fn command_usage(
+        &self,
+        ids: &'static [&'static str],
+        args: impl IntoIterator<Item = String>,
+        _: &str,
+        _: bool,
+    ) -> String {
+        ids.iter()
+            .map(|&i| i.to_owned())
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn argument_desc(&self, name: &'static str, is_required: bool, desc: &str) -> String {
+        format!("{name}{}: {desc}", if is_required { "" } else { "?" })
+    }
+
+    fn command_desc(
+        &self,
+        _: Option<&str>,
+        args: impl IntoIterator<Item = String>,
+        _: Option<&str>,
+    ) -> String {
+        args.into_iter().collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[test]
+fn default_hooks() {
+    assert_eq!(
+        Plain.fold_topic(Cmd::help(Some(CmdId::Ping))),
+        "ping [count] <host>\n\
+         count?: How many times to ping\n\
+         host: The host to ping, e.g. *example_host*"
+    );
+    assert_eq!(
+        Plain.fold_topic(Cmd::help(None::<CmdId>)),
+        "ping [count] <host>\npong"
+    );
+}
+
+#[test]
+fn markdown() {
+    let topic = Cmd::help(Some(CmdId::Ping));
+
+    assert_eq!(
+        MarkdownFoldHelp::new().fold_topic(topic).unwrap(),
+        "**Usage:** `ping [-n|--count <n>] <host>`\n\
+         Ping a host\n\n\
+         **Arguments**\n\
+         - `-n, --count <n>`: How many times to ping\n\
+         - `host`: The host to ping, e.g. *example_host*"
+    );
+    assert!(MarkdownFoldHelp::new()
+        .with_escape(true)
+        .fold_topic(topic)
+        .unwrap()
+        .ends_with("- `host`: The host to ping, e.g. \\*example\\_host\\*"));
+    assert_eq!(
+        MarkdownFoldHelp::new()
+            .fold_topic(Cmd::help(None::<CmdId>))
+            .unwrap(),
+        "Commands to document\n\n\
+         **Commands**\n\
+         - `ping [-n|--count <n>] <host>`: Ping a host\n\
+         - `pong`: Reply to a ping *(deprecated)*"
+    );
+}
+
+#[test]
+fn permissions() {
+    let perms: Vec<_> = Mod::tree()
+        .commands
+        .iter()
+        .map(|c| c.usage.permissions)
+        .collect();
+
+    assert_eq!(perms, [&[][..], &["kick"], &["kick", "ban", "audit"]]);
+
+    let fold = PermissionFoldHelp::new(SimpleFoldHelp, |p: &str| p == "kick");
+
+    assert_eq!(
+        fold.fold_topic(Mod::help(None::<ModId>)).unwrap(),
+        "Moderation commands\n\n\
+         COMMANDS\n  \
+         warn <user>: Warn a user\n  \
+         kick <user>: Kick a user"
+    );
+}
+
+#[test]
+fn choices() {
+    let topic = Tune::help(None::<TuneId>);
+
+    assert!(SimpleFoldHelp.fold_topic(topic).unwrap().ends_with(
+        "ARGUMENTS\n  \
+         -f, --fallback <mode>: one of fast|slow|auto. The mode to use if the first fails\n  \
+         mode (default: auto): one of fast|slow|auto. The new ping mode"
+    ));
+    assert!(MarkdownFoldHelp::new()
+        .fold_topic(topic)
+        .unwrap()
+        .ends_with("- `mode` *(default: `auto`)*: one of `fast|slow|auto`. The new ping mode"));
+}
@@ -0,0 +1,35 @@
+use docbot::{prelude::*, IdParseError};
+
+/// Commands with a hidden member
+#[derive(Docbot, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmd {
+    /// `delete`: Delete one thing
+    Delete,
+    /// `delete-everything`: Delete everything
+    #[docbot(hidden)]
+    DeleteEverything,
+    /// `(secret|psst)`: Do something secret
+    #[docbot(hidden)]
+    Secret,
+}
+
+#[test]
+fn exact_match() {
+    assert_eq!(
+        Cmd::parse(["delete-everything"]).unwrap(),
+        Cmd::DeleteEverything
+    );
+    assert_eq!(Cmd::parse(["psst"]).unwrap(), Cmd::Secret);
+}
+
+#[test]
+fn no_prefix_match() {
+    assert_eq!(Cmd::parse(["del"]).unwrap(), Cmd::Delete);
+
+    for id in ["ps", "secr"] {
+        assert!(
+            matches!(id.parse::<Cmd>(), Err(IdParseError::NoMatch(..))),
+            "{id:?} resolved to a hidden command"
+        );
+    }
+}
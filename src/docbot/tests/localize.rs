@@ -0,0 +1,114 @@
+use docbot::{prelude::*, Localize, LocalizedFold, MessageKey, SimpleFoldError, SimpleFoldHelp};
+
+/// Commands to translate
+#[derive(Docbot, Debug)]
+pub enum Cmd {
+    /// `ping <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// host: The host to ping
+    Ping(String),
+    /// `pong`: Reply to a ping
+    Pong,
+}
+
+/// A fixed table of translations, substituting arguments written as `{name}`
+struct Table(&'static [(&'static str, &'static str)]);
+
+impl Localize for Table {
+    fn localize(&self, key: MessageKey, args: &[(&str, &str)]) -> Option<String> {
+        let key = key.to_string();
+        let (_, msg) = self.0.iter().find(|(k, _)| *k == key)?;
+
+        Some(args.iter().fold((*msg).to_owned(), |s, (k, v)| {
+            s.replace(&format!("{{{k}}}"), v)
+        }))
+    }
+}
+
+const GERMAN: Table = Table(&[
+    ("cmd-ping", "Einen Host anpingen"),
+    (
+        "docbot-missing-required",
+        "Argument '{arg}' für '{cmd}' fehlt",
+    ),
+]);
+
+#[test]
+fn help_fallback() {
+    let fold = LocalizedFold::new(SimpleFoldHelp, GERMAN);
+
+    assert_eq!(
+        fold.fold_topic(Cmd::help(Some(CmdId::Ping))).unwrap(),
+        "USAGE: ping <host>\nEinen Host anpingen\n\nARGUMENTS\n  host: The host to ping"
+    );
+    assert!(fold
+        .fold_topic(Cmd::help(None::<CmdId>))
+        .unwrap()
+        .ends_with("pong: Reply to a ping"));
+}
+
+#[test]
+fn error_fallback() {
+    let fold = LocalizedFold::new(SimpleFoldError, GERMAN);
+    let fold_err = |args: &[&str]| fold.fold_command_parse(Cmd::parse(args).unwrap_err());
+
+    assert_eq!(
+        fold_err(&["ping"]).unwrap(),
+        "Argument 'host' für 'ping' fehlt"
+    );
+    assert_eq!(
+        fold_err(&["pong", "x"]).unwrap(),
+        SimpleFoldError
+            .fold_command_parse(Cmd::parse(["pong", "x"]).unwrap_err())
+            .unwrap()
+    );
+}
+
+#[test]
+fn preferred_languages() {
+    const SWISS: Table = Table(&[("cmd-ping", "En Host aapinge")]);
+
+    let fold = LocalizedFold::new(SimpleFoldHelp, [SWISS, GERMAN]);
+    let help = fold.fold_topic(Cmd::help(Some(CmdId::Ping))).unwrap();
+
+    assert!(help.contains("En Host aapinge"), "{help}");
+
+    let fold = LocalizedFold::new(SimpleFoldError, [SWISS, GERMAN]);
+
+    assert_eq!(
+        fold.fold_command_parse(Cmd::parse(["ping"]).unwrap_err())
+            .unwrap(),
+        "Argument 'host' für 'ping' fehlt"
+    );
+}
+
+#[cfg(feature = "fluent")]
+#[test]
+fn fluent() {
+    use fluent_bundle::{FluentBundle, FluentResource};
+
+    let res = FluentResource::try_new(
+        "cmd-ping = Einen Host anpingen\n    \
+         .arg-host = Der anzupingende Host\n\
+         docbot-missing-required = Argument { $arg } für { $cmd } fehlt\n"
+            .to_owned(),
+    )
+    .unwrap();
+    let mut bundle = FluentBundle::new(vec![]);
+    bundle.set_use_isolating(false);
+    bundle.add_resource(res).unwrap();
+
+    assert_eq!(
+        LocalizedFold::new(SimpleFoldHelp, &bundle)
+            .fold_topic(Cmd::help(Some(CmdId::Ping)))
+            .unwrap(),
+        "USAGE: ping <host>\nEinen Host anpingen\n\nARGUMENTS\n  host: Der anzupingende Host"
+    );
+    assert_eq!(
+        LocalizedFold::new(SimpleFoldError, &bundle)
+            .fold_command_parse(Cmd::parse(["ping"]).unwrap_err())
+            .unwrap(),
+        "Argument host für ping fehlt"
+    );
+}
@@ -0,0 +1,67 @@
+use docbot::{prelude::*, IdParseError};
+
+/// Commands with per-command matching options
+#[derive(Docbot, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmd {
+    /// `get`: Get one value
+    #[docbot(exact, allow_prefix)]
+    Get,
+    /// `getall`: Get every value
+    GetAll,
+    /// `status`: Show the status
+    #[docbot(min_prefix = 4)]
+    Status,
+    /// `Stop`: Stop everything
+    #[docbot(case_sensitive)]
+    Stop,
+}
+
+/// Commands matched exactly and case-sensitively unless overridden
+#[derive(Docbot, Debug, Clone, Copy, PartialEq, Eq)]
+#[docbot(exact, case_sensitive)]
+pub enum Strict {
+    /// `Reset`: Reset the state
+    Reset,
+    /// `Restart`: Restart the bot
+    #[docbot(exact = false, case_sensitive = false)]
+    Restart,
+}
+
+fn no_match<T: CommandId>(s: &str) -> bool {
+    matches!(s.parse::<T>(), Err(IdParseError::NoMatch(..)))
+}
+
+#[test]
+fn exact() {
+    assert_eq!("get".parse::<Cmd>().unwrap(), Cmd::Get);
+    assert_eq!("GET".parse::<Cmd>().unwrap(), Cmd::Get);
+    assert_eq!("ge".parse::<Cmd>().unwrap(), Cmd::GetAll);
+    assert_eq!("g".parse::<Cmd>().unwrap(), Cmd::GetAll);
+    assert_eq!("geta".parse::<Cmd>().unwrap(), Cmd::GetAll);
+}
+
+#[test]
+fn min_prefix() {
+    assert_eq!("stat".parse::<Cmd>().unwrap(), Cmd::Status);
+    assert_eq!("status".parse::<Cmd>().unwrap(), Cmd::Status);
+    assert!(no_match::<Cmd>("sta"));
+    assert_eq!("St".parse::<Cmd>().unwrap(), Cmd::Stop);
+}
+
+#[test]
+fn case_sensitive() {
+    assert_eq!("Sto".parse::<Cmd>().unwrap(), Cmd::Stop);
+    assert!(no_match::<Cmd>("stop"));
+    assert!(no_match::<Cmd>("STOP"));
+}
+
+#[test]
+fn overrides() {
+    assert_eq!("Reset".parse::<Strict>().unwrap(), Strict::Reset);
+    assert!(no_match::<Strict>("reset"));
+    assert!(no_match::<Strict>("Rese"));
+
+    assert_eq!("restart".parse::<Strict>().unwrap(), Strict::Restart);
+    assert_eq!("RESTA".parse::<Strict>().unwrap(), Strict::Restart);
+    assert_eq!("Rest".parse::<Strict>().unwrap(), Strict::Restart);
+}
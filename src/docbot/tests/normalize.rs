@@ -0,0 +1,66 @@
+#![cfg(feature = "unicode")]
+
+use docbot::{prelude::*, IdParseError};
+
+/// Commands matched after Unicode normalization
+#[derive(Docbot, Debug, Clone, Copy, PartialEq, Eq)]
+#[docbot(normalize)]
+pub enum Norm {
+    /// `café`: Order a coffee
+    Cafe,
+    /// `ping`: Ping the bot
+    Ping,
+    /// `İptal`: Cancel
+    Iptal,
+    /// `ılık`: Make it warm
+    Ilik,
+}
+
+/// Commands matched after removing diacritics
+#[derive(Docbot, Debug, Clone, Copy, PartialEq, Eq)]
+#[docbot(strip_diacritics)]
+pub enum Strip {
+    /// `café`: Order a coffee
+    Cafe,
+    /// `İptal`: Cancel
+    Iptal,
+    /// `ılık`: Make it warm
+    Ilik,
+}
+
+fn no_match<T: CommandId>(s: &str) -> bool {
+    matches!(s.parse::<T>(), Err(IdParseError::NoMatch(..)))
+}
+
+#[test]
+fn full_width() {
+    assert_eq!("ｐｉｎｇ".parse::<Norm>().unwrap(), Norm::Ping);
+    assert_eq!("ＰＩＮＧ".parse::<Norm>().unwrap(), Norm::Ping);
+    assert_eq!("ｃａｆé".parse::<Norm>().unwrap(), Norm::Cafe);
+}
+
+#[test]
+fn combining_accents() {
+    assert_eq!("cafe\u{301}".parse::<Norm>().unwrap(), Norm::Cafe);
+    assert_eq!("CAFÉ".parse::<Norm>().unwrap(), Norm::Cafe);
+    assert!(no_match::<Norm>("cafe"));
+
+    assert_eq!("cafe".parse::<Strip>().unwrap(), Strip::Cafe);
+    assert_eq!("CAFE\u{301}".parse::<Strip>().unwrap(), Strip::Cafe);
+}
+
+#[test]
+fn turkish_i() {
+    // İ folds to i with a combining dot above, which only matches a plain i
+    // once diacritics are stripped
+    assert_eq!("İPTAL".parse::<Norm>().unwrap(), Norm::Iptal);
+    assert!(no_match::<Norm>("iptal"));
+    assert_eq!("iptal".parse::<Strip>().unwrap(), Strip::Iptal);
+    assert_eq!("IPTAL".parse::<Strip>().unwrap(), Strip::Iptal);
+
+    // ı has no case mapping to I or i under the default case fold
+    assert_eq!("ılık".parse::<Norm>().unwrap(), Norm::Ilik);
+    assert!(no_match::<Norm>("ilik"));
+    assert!(no_match::<Strip>("ILIK"));
+    assert!(no_match::<Strip>("ıptal"));
+}
@@ -0,0 +1,39 @@
+use docbot::{prelude::*, ArgumentName, CommandParseError};
+
+/// Commands with custom argument parsers
+#[derive(Docbot, Debug)]
+pub enum Cmd {
+    /// `sleep <durations...>`: Sleep for the given durations
+    ///
+    /// # Arguments
+    /// durations: How long to sleep, e.g. 5s
+    Sleep(#[docbot(parse_with = parse_duration)] Vec<u64>),
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("bad duration {0:?}")]
+struct BadDuration(String);
+
+fn parse_duration(s: &str) -> Result<u64, BadDuration> {
+    s.strip_suffix('s')
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| BadDuration(s.into()))
+}
+
+#[test]
+fn rest() {
+    match Cmd::parse(["sleep", "5s", "2s"]).unwrap() {
+        Cmd::Sleep(d) => assert_eq!(d, [5, 2]),
+    }
+}
+
+#[test]
+fn error_unchanged() {
+    match Cmd::parse(["sleep", "5s", "soon"]).unwrap_err() {
+        CommandParseError::BadConvert(ArgumentName { cmd, arg }, err, None) => {
+            assert_eq!((cmd, arg), ("sleep", "durations"));
+            assert_eq!(err.downcast_ref(), Some(&BadDuration("soon".into())));
+        },
+        e => panic!("unexpected error {e:?}"),
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::Cursor;
+
+use docbot::{prelude::*, repl::Repl};
+
+/// Commands for testing the REPL
+#[derive(Docbot, Debug)]
+pub enum Cmd {
+    /// `echo <text>`: Print some text
+    ///
+    /// # Arguments
+    /// text: The text to print
+    Echo(String),
+    /// `fail`: Always fail
+    Fail,
+    /// `quiet`: Print nothing
+    Quiet,
+}
+
+fn run(input: &str) -> String {
+    let mut repl = Repl::<Cmd, _>::new(|cmd| match cmd {
+        Cmd::Echo(s) => Ok(Some(s)),
+        Cmd::Fail => Err(anyhow::anyhow!("it failed")),
+        Cmd::Quiet => Ok(None),
+    })
+    .with_prompt("$ ");
+    let mut output = vec![];
+
+    repl.run(Cursor::new(input), &mut output).unwrap();
+
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn prompt() {
+    assert_eq!(run(""), "$ ");
+    assert_eq!(run("\n"), "$ $ ");
+    assert_eq!(run("quiet\n"), "$ $ ");
+}
+
+#[test]
+fn responses() {
+    assert_eq!(run("echo hi\necho 'a b'\n"), "$ hi\n$ a b\n$ ");
+    assert_eq!(run("fail\n"), "$ it failed\n$ ");
+}
+
+#[test]
+fn eof_without_newline() {
+    assert_eq!(run("echo hi"), "$ hi\n$ ");
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        run("echo\n"),
+        "$ Missing required argument 'text' to command 'echo'  See `help echo`.\n\
+         Usage: echo <text>\n  \
+         echo\n      \
+         ^\n\
+         $ "
+    );
+}
+
+#[test]
+fn help() {
+    let out = run("help echo\n");
+
+    assert!(
+        out.starts_with("$ USAGE: echo <text>\nPrint some text\n"),
+        "{out:?}"
+    );
+    assert!(out.ends_with("\n$ "), "{out:?}");
+}
@@ -0,0 +1,86 @@
+use docbot::{prelude::*, CommandTree};
+
+/// Top-level commands
+#[derive(Docbot)]
+pub enum Cmd {
+    /// `ping <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// host: The host to ping
+    Ping(String),
+    /// `admin <command...>`: Run an administrative command
+    ///
+    /// # Arguments
+    /// command: The command to run
+    #[docbot(subcommand)]
+    Admin(Admin),
+}
+
+/// Administrative commands
+#[derive(Docbot)]
+pub enum Admin {
+    /// `ban <user>`: Ban a user
+    ///
+    /// # Arguments
+    /// user: The user to ban
+    Ban(String),
+    /// `purge`: Delete everything
+    #[docbot(hidden)]
+    Purge,
+}
+
+fn walk(tree: &'static CommandTree, prefix: &str, out: &mut Vec<String>) {
+    for node in tree.commands {
+        let path = format!("{prefix}{}", node.usage.ids[0]);
+
+        if let Some(sub) = node.subtree() {
+            walk(sub, &format!("{path} "), out);
+        }
+
+        out.push(path);
+    }
+}
+
+#[test]
+fn walk_tree() {
+    let mut paths = vec![];
+    walk(Cmd::tree(), "", &mut paths);
+
+    assert_eq!(paths, ["ping", "admin ban", "admin purge", "admin"]);
+    assert_eq!(Cmd::tree().summary, Some("Top-level commands"));
+}
+
+#[test]
+fn find() {
+    let ban = Cmd::tree().find(&["admin", "ban"]).unwrap();
+
+    assert_eq!(ban.usage.desc, "Ban a user");
+    assert_eq!(ban.desc.args[0].name, "user");
+    assert!(Cmd::tree().find(&["admin", "purge"]).unwrap().hidden);
+    assert!(Cmd::tree().find(&["ping", "ban"]).is_none());
+    assert!(Cmd::tree().find::<&str>(&[]).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize() {
+    use serde_json::json;
+
+    let tree = serde_json::to_value(Cmd::tree()).unwrap();
+    let ping = &tree["commands"][0];
+    let admin = &tree["commands"][1];
+
+    assert_eq!(tree["summary"], "Top-level commands");
+    assert_eq!(ping["usage"]["ids"], json!(["ping"]));
+    assert_eq!(ping["desc"]["args"][0]["name"], "host");
+    assert_eq!(ping["subcommands"], json!(null));
+
+    let sub = &admin["subcommands"];
+    assert_eq!(sub["summary"], "Administrative commands");
+    assert_eq!(sub["commands"][0]["usage"]["ids"], json!(["ban"]));
+    assert_eq!(
+        sub["commands"][0]["desc"]["args"][0]["desc"],
+        "The user to ban"
+    );
+    assert_eq!(sub["commands"][1]["hidden"], true);
+}
@@ -0,0 +1,5 @@
+// Diagnostics differ when nightly proc macro features are enabled
+#![cfg(not(feature = "nightly"))]
+
+#[test]
+fn ui() { trybuild::TestCases::new().compile_fail("tests/ui/*.rs"); }
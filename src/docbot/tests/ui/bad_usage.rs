@@ -0,0 +1,18 @@
+use docbot::prelude::*;
+
+/// Commands with a malformed usage line
+#[derive(Docbot)]
+pub enum Cmd {
+    /// `ping <host>`: Ping a host
+    ///
+    /// # Arguments
+    /// host: The host to ping
+    Ping(String),
+    /// `kick <user> extra`: Kick a user
+    ///
+    /// # Arguments
+    /// user: The user to kick
+    Kick(String),
+}
+
+fn main() {}
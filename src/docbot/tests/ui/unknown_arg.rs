@@ -0,0 +1,14 @@
+use docbot::prelude::*;
+
+/// `kick <user> [reason]`: Kick a user
+///
+/// # Arguments
+/// user: The user to kick
+/// reson: Why they were kicked
+#[derive(Docbot)]
+pub struct Kick {
+    user: String,
+    reason: Option<String>,
+}
+
+fn main() {}
@@ -1,17 +1,61 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, iter::Peekable, ops::Range, str::CharIndices, sync::LazyLock};
 
-use lazy_static::lazy_static;
 use regex::Regex;
 
-lazy_static! {
-    static ref COMMAND_ARG_RE: Regex =
-        Regex::new(r#"\s*(?:([^'"\s]\S*)|'([^']*)'|"((?:[^"\\]|\\.)*)")"#).unwrap();
-    static ref COMMAND_DQUOTE_ESCAPE_RE: Regex = Regex::new(r"\\(.)").unwrap();
+use super::TokenizeError;
+
+/// A byte range within an input string
+pub type Span = Range<usize>;
+
+/// A command argument which may carry its location in the input string
+pub trait Token: AsRef<str> {
+    /// Get the location of this token in the input, if known
+    fn span(&self) -> Option<Span>;
+}
+
+/// A value annotated with its location in the input string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    /// The annotated value
+    pub value: T,
+    /// The location of the value in the input
+    pub span: Span,
+}
+
+impl<T: AsRef<str>> AsRef<str> for Spanned<T> {
+    fn as_ref(&self) -> &str { self.value.as_ref() }
+}
+
+impl<T: AsRef<str>> Token for Spanned<T> {
+    fn span(&self) -> Option<Span> { Some(self.span.clone()) }
+}
+
+impl<T: Token> Token for &T {
+    fn span(&self) -> Option<Span> { (**self).span() }
+}
+
+/// A command argument with no location information
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Unspanned<S>(pub S);
+
+impl<S: AsRef<str>> AsRef<str> for Unspanned<S> {
+    fn as_ref(&self) -> &str { self.0.as_ref() }
+}
+
+impl<S: AsRef<str>> Token for Unspanned<S> {
+    fn span(&self) -> Option<Span> { None }
 }
 
+static COMMAND_ARG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\s*(?:([^'"\s]\S*)|'([^']*)'|"((?:[^"\\]|\\.)*)")"#).unwrap());
+static COMMAND_DQUOTE_ESCAPE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\(.)").unwrap());
+
 /// Performs simple tokenization of a string with minimal support for single-
 /// and double-quoting
-pub fn tokenize_str_simple(s: &str) -> impl Iterator<Item = Cow<str>> {
+///
+/// Malformed input is tokenized on a best-effort basis; see [`tokenize_str`]
+/// for a stricter tokenizer that reports errors.
+pub fn tokenize_str_simple(s: &str) -> impl Iterator<Item = Cow<'_, str>> {
     COMMAND_ARG_RE.captures_iter(s).map(|cap| {
         cap.get(3).map_or_else(
             || {
@@ -24,3 +68,253 @@ pub fn tokenize_str_simple(s: &str) -> impl Iterator<Item = Cow<str>> {
         )
     })
 }
+
+/// Iterator over the words of a string, returned by [`tokenize_str`]
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Tokens<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+    done: bool,
+}
+
+/// Iterator over the words of a string and their locations, returned by
+/// [`Tokens::spanned`]
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SpannedTokens<'a>(Tokens<'a>);
+
+impl<'a> Tokens<'a> {
+    /// Annotate each word with its location in the input string
+    pub fn spanned(self) -> SpannedTokens<'a> { SpannedTokens(self) }
+
+    fn next_spanned(&mut self) -> Option<Result<Spanned<String>, TokenizeError>> {
+        while !self.done {
+            self.skip_whitespace();
+
+            let Some(&(start, _)) = self.chars.peek() else {
+                self.done = true;
+                break;
+            };
+
+            match self.word() {
+                Ok(Some(value)) => {
+                    let end = self.chars.peek().map_or(self.len, |(i, _)| *i);
+
+                    return Some(Ok(Spanned {
+                        value,
+                        span: start..end,
+                    }));
+                },
+                Ok(None) => (),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                },
+            }
+        }
+
+        None
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn single_quote(&mut self, start: usize, word: &mut String) -> Result<(), TokenizeError> {
+        loop {
+            match self.chars.next() {
+                Some((_, '\'')) => break Ok(()),
+                Some((_, c)) => word.push(c),
+                None => break Err(TokenizeError::UnterminatedQuote('\'', start)),
+            }
+        }
+    }
+
+    fn double_quote(&mut self, start: usize, word: &mut String) -> Result<(), TokenizeError> {
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => break Ok(()),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '\n')) => (),
+                    Some((_, c @ ('$' | '`' | '"' | '\\'))) => word.push(c),
+                    Some((_, c)) => {
+                        word.push('\\');
+                        word.push(c);
+                    },
+                    None => break Err(TokenizeError::UnterminatedQuote('"', start)),
+                },
+                Some((_, c)) => word.push(c),
+                None => break Err(TokenizeError::UnterminatedQuote('"', start)),
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<Option<String>, TokenizeError> {
+        let mut word = String::new();
+        let mut any = false;
+
+        while let Some((i, c)) = self.chars.next_if(|(_, c)| !c.is_whitespace()) {
+            match c {
+                '\\' => match self.chars.next() {
+                    Some((_, '\n')) => continue,
+                    Some((_, c)) => word.push(c),
+                    None => return Err(TokenizeError::DanglingEscape(i)),
+                },
+                '\'' => self.single_quote(i, &mut word)?,
+                '"' => self.double_quote(i, &mut word)?,
+                c => word.push(c),
+            }
+
+            any = true;
+        }
+
+        Ok(if any { Some(word) } else { None })
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Result<String, TokenizeError>;
+
+    fn next(&mut self) -> Option<Self::Item> { self.next_spanned().map(|r| r.map(|s| s.value)) }
+}
+
+impl Iterator for SpannedTokens<'_> {
+    type Item = Result<Spanned<String>, TokenizeError>;
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next_spanned() }
+}
+
+/// Tokenize a string using POSIX shell quoting rules
+///
+/// Words are separated by unquoted whitespace.  Single quotes preserve their
+/// contents literally, double quotes allow escaping `$`, `` ` ``, `"` and `\`,
+/// and a backslash outside of quotes escapes the following character.
+/// Adjacent quoted and unquoted segments are joined into a single word.
+///
+/// Iteration stops after the first error.  Use [`Tokens::spanned`] to retrieve
+/// the location of each word as well.
+pub fn tokenize_str(s: &str) -> Tokens<'_> {
+    Tokens {
+        chars: s.char_indices().peekable(),
+        len: s.len(),
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<String> { tokenize_str(s).collect::<Result<_, _>>().unwrap() }
+
+    fn spans(s: &str) -> Vec<(String, Span)> {
+        tokenize_str(s)
+            .spanned()
+            .map(|t| t.map(|Spanned { value, span }| (value, span)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn error(s: &str) -> TokenizeError {
+        tokenize_str(s)
+            .find_map(Result::err)
+            .unwrap_or_else(|| panic!("{s:?} tokenized without errors"))
+    }
+
+    #[test]
+    fn empty() {
+        assert!(words("").is_empty());
+        assert!(words(" \t\n ").is_empty());
+        assert_eq!(words("''"), [""]);
+        assert_eq!(words(r#"a "" b"#), ["a", "", "b"]);
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(words("foo bar"), ["foo", "bar"]);
+        assert_eq!(words("  foo \t\n bar  "), ["foo", "bar"]);
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(words(r"'foo bar' baz"), ["foo bar", "baz"]);
+        assert_eq!(words(r#""foo bar" baz"#), ["foo bar", "baz"]);
+        assert_eq!(words(r#"a'b c'"d e"f"#), ["ab cd ef"]);
+        assert_eq!(words(r#"'"' "'""#), ["\"", "'"]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(words(r"foo\ bar"), ["foo bar"]);
+        assert_eq!(words(r#"\'\"\\"#), [r#"'"\"#]);
+        assert_eq!(words("foo\\\nbar"), ["foobar"]);
+        assert_eq!(words(r"'a\nb'"), [r"a\nb"]);
+        assert_eq!(words(r#""\$\`\"\\""#), [r#"$`"\"#]);
+        assert_eq!(words(r#""\a\n""#), [r"\a\n"]);
+        assert_eq!(words("\"a\\\nb\""), ["ab"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            error("'foo"),
+            TokenizeError::UnterminatedQuote('\'', 0)
+        ));
+        assert!(matches!(
+            error(r#"a "foo"#),
+            TokenizeError::UnterminatedQuote('"', 2)
+        ));
+        assert!(matches!(
+            error(r#"a "foo\"#),
+            TokenizeError::UnterminatedQuote('"', 2)
+        ));
+        assert!(matches!(error(r"foo\"), TokenizeError::DanglingEscape(3)));
+        assert!(matches!(
+            error("é 'ü"),
+            TokenizeError::UnterminatedQuote('\'', 3)
+        ));
+        assert!(matches!(error(r"ü\"), TokenizeError::DanglingEscape(2)));
+    }
+
+    #[test]
+    fn stops_after_error() {
+        let toks: Vec<_> = tokenize_str("a 'b c").collect();
+
+        assert_eq!(toks.len(), 2);
+        assert_eq!(toks[0].as_deref().unwrap(), "a");
+        assert!(toks[1].is_err());
+    }
+
+    #[test]
+    fn ascii_spans() {
+        assert_eq!(spans("foo  bar"), [
+            ("foo".into(), 0..3),
+            ("bar".into(), 5..8)
+        ]);
+        assert_eq!(spans(r#" 'a b'"c" "#), [("a bc".into(), 1..9)]);
+        assert_eq!(spans(r"a\ b"), [("a b".into(), 0..4)]);
+    }
+
+    #[test]
+    fn multibyte_spans() {
+        let input = "héllo 'wörld' 日本";
+
+        assert_eq!(spans(input), [
+            ("héllo".into(), 0..6),
+            ("wörld".into(), 7..15),
+            ("日本".into(), 16..22),
+        ]);
+
+        for (value, span) in spans(input) {
+            assert_eq!(input[span].trim_matches('\''), value);
+        }
+    }
+
+    #[test]
+    fn simple() {
+        let words: Vec<_> = tokenize_str_simple(r#"foo 'bar baz' "a \"b\"""#).collect();
+
+        assert_eq!(words, ["foo", "bar baz", r#"a "b""#]);
+    }
+}
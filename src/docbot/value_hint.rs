@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use super::CommandId;
+
+/// A type with a fixed set of valid string values, which can be suggested when
+/// an argument of this type fails to parse
+///
+/// This is implemented for every [`CommandId`], including `#[derive(Docbot)]`
+/// enums with only unit variants, listing their visible names, and for the
+/// [`CommandPath`](crate::CommandPath) types generated by `#[derive(Docbot)]`,
+/// listing the visible names accepted at the head of the path.  Arguments of a
+/// type implementing this trait report their valid values in
+/// [`CommandParseError::BadConvert`](crate::CommandParseError::BadConvert).
+pub trait ValueHint {
+    /// List the values that should be suggested to users
+    fn values() -> &'static [&'static str];
+
+    /// List the canonical form of each of [`values`](Self::values), in the
+    /// same order, so that several aliases of one value are suggested only once
+    #[must_use]
+    fn canonical_values() -> &'static [&'static str] { Self::values() }
+}
+
+impl<T: CommandId> ValueHint for T {
+    fn values() -> &'static [&'static str] { T::visible_names() }
+
+    fn canonical_values() -> &'static [&'static str] { T::visible_canonical_names() }
+}
+
+/// The input given for an argument that failed to convert, along with the
+/// values its type accepts according to [`ValueHint`]
+#[derive(Debug, Clone)]
+pub struct ArgumentValue {
+    /// The input that failed to convert
+    pub given: String,
+    /// The valid values of the argument
+    pub values: &'static [&'static str],
+    /// The canonical form of each of [`values`](Self::values)
+    pub canonical: &'static [&'static str],
+}
+
+// Support for `#[derive(Docbot)]`, which looks up the value hint of a field's
+// type if it has one using autoref specialization: the method of
+// `HasValueHint` is found before that of `NoValueHint` if it applies, since it
+// takes `Probe` by reference rather than by double reference.
+
+#[doc(hidden)]
+#[allow(missing_debug_implementations)]
+pub struct Probe<T>(pub PhantomData<T>);
+
+#[doc(hidden)]
+pub trait HasValueHint {
+    fn value_hint(&self, given: &str) -> Option<ArgumentValue>;
+
+    fn canonical_values(&self) -> Option<&'static [&'static str]>;
+}
+
+impl<T: ValueHint> HasValueHint for Probe<T> {
+    fn value_hint(&self, given: &str) -> Option<ArgumentValue> {
+        Some(ArgumentValue {
+            given: given.into(),
+            values: T::values(),
+            canonical: T::canonical_values(),
+        })
+    }
+
+    fn canonical_values(&self) -> Option<&'static [&'static str]> { Some(T::canonical_values()) }
+}
+
+#[doc(hidden)]
+pub trait NoValueHint {
+    fn value_hint(&self, given: &str) -> Option<ArgumentValue> {
+        let _ = given;
+        None
+    }
+
+    fn canonical_values(&self) -> Option<&'static [&'static str]> { None }
+}
+
+impl<T> NoValueHint for &Probe<T> {}